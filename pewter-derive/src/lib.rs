@@ -0,0 +1,269 @@
+/*!
+ * Derive macros for pewter's `DataType` and `RecordType` traits.
+ *
+ * ```ignore
+ * #[derive(Clone, Copy, Debug, Default, DataType, RecordType)]
+ * struct Particle {
+ *   id: u32,
+ *   pos: [f32; 3],
+ *   bounds: Rect,
+ * }
+ * ```
+ *
+ * Scalar fields (`u32`, `i32`, `f32`), vector fields (arrays of
+ * 2 to 4 scalars) and matrix fields (arrays of 2 to 4 columns, each
+ * an array of 2 to 4 `f32`) are primitive fields.  `FixedArray`
+ * fields are arrays.  Fields of any other named type are nested
+ * records, which must implement `RecordType` themselves.
+ *
+ * The generated code names the crate as `::pewter`, which can be
+ * changed with `#[pewter(crate = "path")]`.
+ */
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+  parse_macro_input,
+  spanned::Spanned,
+  Data,
+  DeriveInput,
+  Expr,
+  Fields,
+  Lit,
+  Meta,
+  NestedMeta,
+  Path,
+  Type,
+};
+
+/** The scalar types a field can hold. */
+const SCALAR_TYPES: &[&str] = &["u32", "i32", "f32"];
+
+/** The name of pewter's fixed-size array type. */
+const ARRAY_TYPE: &str = "FixedArray";
+
+/** The name of pewter's atomic type, which records can't hold. */
+const ATOMIC_TYPE: &str = "Atomic";
+
+/** Rust types with no GPU representation in pewter. */
+const UNSUPPORTED_TYPES: &[&str] = &[
+  "u8", "u16", "u64", "u128", "usize",
+  "i8", "i16", "i64", "i128", "isize",
+  "f64", "bool", "char", "str", "String",
+];
+
+#[proc_macro_derive(DataType, attributes(pewter))]
+pub fn derive_data_type(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let result = check_record_struct(&input, "DataType").and_then(|_| {
+    let krate = crate_path(&input) ?;
+    let name = &input.ident;
+    Ok(quote! {
+      impl #krate::DataType for #name {
+        fn specialize<S: #krate::DataTypeSpecialize>(spez: S)
+          -> S::Result
+        {
+          spez.record::<Self>()
+        }
+        fn specialize_ref<S: #krate::DataTypeSpecializeRef>(&self,
+          spez: S
+        ) {
+          spez.record(self)
+        }
+        fn specialize_mut<S: #krate::DataTypeSpecializeMut>(&mut self,
+          spez: S
+        ) {
+          spez.record(self)
+        }
+      }
+    })
+  });
+  result.unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+#[proc_macro_derive(RecordType, attributes(pewter))]
+pub fn derive_record_type(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let result = check_record_struct(&input, "RecordType").and_then(|fields| {
+    let krate = crate_path(&input) ?;
+    let name = &input.ident;
+    // Unsupported fields are reported together, inside the impl so
+    // that uses of the record don't report it as missing.
+    let specs = fields.iter()
+      .map(|field| field_spec(field).unwrap_or_else(|err| {
+        err.to_compile_error()
+      }))
+      .collect::<Vec<_>>();
+    Ok(quote! {
+      impl #krate::RecordType for #name {
+        fn specify_fields<FS>(fs: &mut FS)
+          where FS: #krate::RecordTypeFieldSpecifier<Self>
+        {
+          #(#specs)*
+        }
+      }
+    })
+  });
+  result.unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+/** The path to the pewter crate, from `#[pewter(crate = "path")]`. */
+fn crate_path(input: &DeriveInput) -> syn::Result<Path> {
+  let mut krate = syn::parse_quote!(::pewter);
+  for attr in input.attrs.iter().filter(|a| a.path.is_ident("pewter")) {
+    let list = match attr.parse_meta() ? {
+      Meta::List(list) => list,
+      meta => return Err(syn::Error::new(meta.span(),
+        "expected #[pewter(crate = \"path\")]")),
+    };
+    for nested in list.nested.iter() {
+      match nested {
+        NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("crate") => {
+          krate = match &nv.lit {
+            Lit::Str(path) => path.parse() ?,
+            lit => return Err(syn::Error::new(lit.span(),
+              "expected the crate path as a string")),
+          };
+        },
+        _ => return Err(syn::Error::new(nested.span(),
+          "unknown pewter attribute, expected `crate = \"path\"`")),
+      }
+    }
+  }
+  Ok(krate)
+}
+
+/**
+ * Check the input is a non-generic struct with named fields, and
+ * return its fields.
+ */
+fn check_record_struct<'a>(input: &'a DeriveInput, derive: &str)
+  -> syn::Result<Vec<&'a syn::Field>>
+{
+  if !input.generics.params.is_empty() {
+    return Err(syn::Error::new(input.generics.span(),
+      format!("#[derive({})] does not support generic records", derive)));
+  }
+  let data = match &input.data {
+    Data::Struct(data) => data,
+    _ => return Err(syn::Error::new(input.ident.span(),
+      format!("#[derive({})] is only supported on structs", derive))),
+  };
+  let fields = match &data.fields {
+    Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+    _ => return Err(syn::Error::new(input.ident.span(),
+      format!("#[derive({})] requires a struct with named fields", derive))),
+  };
+  if fields.is_empty() {
+    return Err(syn::Error::new(input.ident.span(),
+      format!("#[derive({})] requires at least one field", derive)));
+  }
+  Ok(fields)
+}
+
+/** How a field is specified to the record builder. */
+enum FieldKind {
+  Prim,
+  Record,
+  Array,
+}
+
+/** Generate the `specify_fields` call for one field. */
+fn field_spec(field: &&syn::Field) -> syn::Result<TokenStream2> {
+  let ident = field.ident.as_ref().expect("Named field without a name");
+  let name = ident.to_string();
+  let name = name.strip_prefix("r#").unwrap_or(&name);
+  let ty = &field.ty;
+  let span = ty.span();
+  let spec = match classify_type(ty) ? {
+    FieldKind::Prim => quote_spanned! { span =>
+      fs.prim_field::<#ty, _, _>(#name,
+        |r| r.#ident,
+        |r, v| r.#ident = v);
+    },
+    FieldKind::Record => quote_spanned! { span =>
+      fs.record_field::<#ty, _, _>(#name,
+        |r| r.#ident,
+        |r, v| r.#ident = v);
+    },
+    FieldKind::Array => quote_spanned! { span =>
+      fs.array_field::<#ty, _, _>(#name,
+        |r| r.#ident,
+        |r, v| r.#ident = v);
+    },
+  };
+  Ok(spec)
+}
+
+fn classify_type(ty: &Type) -> syn::Result<FieldKind> {
+  match ty {
+    Type::Path(path) if path.qself.is_none() => {
+      let last = path.path.segments.last()
+        .expect("Type path without segments");
+      let ident = last.ident.to_string();
+      if path.path.segments.len() == 1 && SCALAR_TYPES.contains(&&*ident) {
+        return Ok(FieldKind::Prim);
+      }
+      if ident == ARRAY_TYPE {
+        return Ok(FieldKind::Array);
+      }
+      if ident == ATOMIC_TYPE {
+        return Err(unsupported(ty,
+          "atomic fields are not supported, atomics can only be held \
+           directly by buffers"));
+      }
+      if UNSUPPORTED_TYPES.contains(&&*ident) {
+        return Err(unsupported(ty, &format!(
+          "`{}` fields are not supported, use one of {}",
+          ident, SCALAR_TYPES.join(", "))));
+      }
+      Ok(FieldKind::Record)
+    },
+    Type::Array(array) => {
+      if let Type::Array(column) = &*array.elem {
+        if !is_vector(column, &["f32"]) || !is_component_count(&array.len) {
+          return Err(unsupported(ty,
+            "matrix fields must be arrays of 2 to 4 columns of [f32; 2] \
+             to [f32; 4]"));
+        }
+        return Ok(FieldKind::Prim);
+      }
+      if !is_vector(array, SCALAR_TYPES) {
+        return Err(unsupported(ty,
+          "vector fields must be arrays of 2 to 4 u32, i32 or f32, \
+           use FixedArray for other arrays"));
+      }
+      Ok(FieldKind::Prim)
+    },
+    Type::Paren(paren) => classify_type(&paren.elem),
+    Type::Group(group) => classify_type(&group.elem),
+    _ => Err(unsupported(ty,
+      "fields must be scalars, vectors or records")),
+  }
+}
+
+/** Whether `array` is an array of 2 to 4 of one of `scalars`. */
+fn is_vector(array: &syn::TypeArray, scalars: &[&str]) -> bool {
+  let elem_ok = match &*array.elem {
+    Type::Path(path) => path.path.get_ident()
+      .is_some_and(|i| scalars.contains(&&*i.to_string())),
+    _ => false,
+  };
+  elem_ok && is_component_count(&array.len)
+}
+
+/** Whether `len` is a literal 2, 3 or 4. */
+fn is_component_count(len: &Expr) -> bool {
+  let len = match len {
+    Expr::Lit(lit) => match &lit.lit {
+      Lit::Int(int) => int.base10_parse::<usize>().ok(),
+      _ => None,
+    },
+    _ => None,
+  };
+  matches!(len, Some(2 ..= 4))
+}
+
+fn unsupported(ty: &Type, message: &str) -> syn::Error {
+  syn::Error::new(ty.span(), message)
+}
@@ -0,0 +1,49 @@
+
+/**
+ * How a shader file accesses a buffer.  WGSL has no write-only
+ * storage buffers, so buffers written by a shader are read-write.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BufferAccess {
+  // `var<storage, read>`
+  Read,
+  // `var<storage, read_write>`
+  ReadWrite,
+  // `var<uniform>`, holding a single value.
+  Uniform,
+}
+impl BufferAccess {
+  pub fn is_uniform(&self) -> bool {
+    *self == BufferAccess::Uniform
+  }
+}
+
+/**
+ * Type-level buffer access, carried by a `SessionBuffer` so that
+ * misuse of a buffer is caught when a shader is declared.
+ */
+pub trait AccessMode: 'static {
+  const ACCESS: BufferAccess;
+}
+/** Access modes of storage buffers, which are arrays. */
+pub trait StorageAccess: AccessMode {}
+/** Access modes of buffers which a shader can store to. */
+pub trait WritableAccess: StorageAccess {}
+
+pub struct ReadAccess;
+impl AccessMode for ReadAccess {
+  const ACCESS: BufferAccess = BufferAccess::Read;
+}
+impl StorageAccess for ReadAccess {}
+
+pub struct ReadWriteAccess;
+impl AccessMode for ReadWriteAccess {
+  const ACCESS: BufferAccess = BufferAccess::ReadWrite;
+}
+impl StorageAccess for ReadWriteAccess {}
+impl WritableAccess for ReadWriteAccess {}
+
+pub struct UniformAccess;
+impl AccessMode for UniformAccess {
+  const ACCESS: BufferAccess = BufferAccess::Uniform;
+}
@@ -1,7 +1,11 @@
 
-use wgpu;
 use std::{
-  sync::Arc,
+  sync::{Arc, mpsc},
+};
+use crate::{
+  types::{SharedName, DataTypeInfo},
+  buf::{BufferShape, tail_offset},
+  PewterError,
 };
 
 /**
@@ -9,9 +13,259 @@ use std::{
  * but whenever a gpu buffer exists associated with a Peweter
  * instance, it is fronted by this proxy type.
  * 
- * Carries a DataTypeInfo and a dimensionalized size.
+ * Carries a DataTypeInfo and a dimensionalized size.  Uniform
+ * buffers hold a single value.  Tail buffers hold a header value
+ * followed by a runtime-sized array.
  */
 #[derive(Clone)]
 pub(crate) struct Buffer {
   buffer: Arc<wgpu::Buffer>,
-}
\ No newline at end of file
+  name: SharedName,
+  data_type: DataTypeInfo,
+  shape: BufferShape,
+  uniform: bool,
+
+  // The type of the value preceding the array, for tail buffers.
+  header: Option<DataTypeInfo>,
+
+  // The device and queue the buffer was created on.
+  device: Arc<wgpu::Device>,
+  queue: Arc<wgpu::Queue>,
+}
+impl Buffer {
+  pub(crate) fn new(
+    device: &Arc<wgpu::Device>,
+    queue: &Arc<wgpu::Queue>,
+    name: SharedName,
+    data_type: DataTypeInfo,
+    shape: BufferShape,
+  ) -> Result<Self, PewterError> {
+    Buffer::create(device, queue, name, data_type, shape, false, None)
+  }
+
+  pub(crate) fn new_uniform(
+    device: &Arc<wgpu::Device>,
+    queue: &Arc<wgpu::Queue>,
+    name: SharedName,
+    data_type: DataTypeInfo,
+  ) -> Result<Self, PewterError> {
+    let shape = BufferShape::new_1d(1);
+    Buffer::create(device, queue, name, data_type, shape, true, None)
+  }
+
+  pub(crate) fn new_tail(
+    device: &Arc<wgpu::Device>,
+    queue: &Arc<wgpu::Queue>,
+    name: SharedName,
+    header: DataTypeInfo,
+    data_type: DataTypeInfo,
+    len: usize,
+  ) -> Result<Self, PewterError> {
+    // A runtime-sized array can only be bound with at least one
+    // element, so an empty array is given one, which `arrayLength`
+    // reports on the gpu.
+    let len = usize::max(len, 1);
+    let extent = u32::try_from(len).map_err(|_| {
+      PewterError::BufferTooLarge { buffer: name.clone(), len }
+    }) ?;
+    let shape = BufferShape::new_1d(extent);
+    let header = Some(header);
+    Buffer::create(device, queue, name, data_type, shape, false, header)
+  }
+
+  fn create(
+    device: &Arc<wgpu::Device>,
+    queue: &Arc<wgpu::Queue>,
+    name: SharedName,
+    data_type: DataTypeInfo,
+    shape: BufferShape,
+    uniform: bool,
+    header: Option<DataTypeInfo>,
+  ) -> Result<Self, PewterError> {
+    check_buffer_size(device, &name, header.as_ref(), &data_type, &shape,
+      uniform) ?;
+    let size =
+      tail_buffer_byte_size(header.as_ref(), &data_type, shape.len());
+    let binding_usage = if uniform {
+      wgpu::BufferUsages::UNIFORM
+    } else {
+      wgpu::BufferUsages::STORAGE
+    };
+    let buffer = Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some(name.as_ref()),
+      size: size as wgpu::BufferAddress,
+      usage: binding_usage
+           | wgpu::BufferUsages::COPY_SRC
+           | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    }));
+    let device = device.clone();
+    let queue = queue.clone();
+    Ok(Buffer {
+      buffer, name, data_type, shape, uniform, header, device, queue
+    })
+  }
+
+  pub(crate) fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+  pub(crate) fn shape(&self) -> &BufferShape { &self.shape }
+  pub(crate) fn len(&self) -> usize { self.shape.len() }
+  pub(crate) fn is_uniform(&self) -> bool { self.uniform }
+  pub(crate) fn header(&self) -> Option<&DataTypeInfo> {
+    self.header.as_ref()
+  }
+  pub(crate) fn wgpu_buffer(&self) -> &wgpu::Buffer { &self.buffer }
+
+  /** Whether `self` and `other` front the same gpu buffer. */
+  pub(crate) fn same_buffer(&self, other: &Buffer) -> bool {
+    Arc::ptr_eq(&self.buffer, &other.buffer)
+  }
+
+  /** The size of the underlying gpu buffer in bytes. */
+  pub(crate) fn byte_size(&self) -> usize {
+    let header = self.header.as_ref();
+    tail_buffer_byte_size(header, &self.data_type, self.len())
+  }
+
+  /** Write bytes into the start of the buffer. */
+  pub(crate) fn write_bytes(&self, bytes: &[u8]) {
+    self.queue.write_buffer(&self.buffer, 0, bytes);
+  }
+
+  /**
+   * Encode a copy of the buffer's contents into a new mappable
+   * staging buffer, which is returned.
+   */
+  pub(crate) fn encode_download(&self, encoder: &mut wgpu::CommandEncoder)
+    -> wgpu::Buffer
+  {
+    let size = self.byte_size() as wgpu::BufferAddress;
+    let download = self.device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("pewter_download"),
+      size,
+      usage: wgpu::BufferUsages::MAP_READ
+           | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+    encoder.copy_buffer_to_buffer(&self.buffer, 0, &download, 0, size);
+    download
+  }
+
+  /** Read back the entire contents of the buffer. */
+  pub(crate) async fn read_bytes(&self) -> Result<Vec<u8>, PewterError> {
+    let mut encoder = self.device.create_command_encoder(
+      &wgpu::CommandEncoderDescriptor { label: Some(self.name.as_ref()) }
+    );
+    let download = self.encode_download(&mut encoder);
+    self.queue.submit(Some(encoder.finish()));
+    read_mappable_buffer(&self.device, &download).await
+  }
+}
+
+/**
+ * Compute the byte size of a gpu buffer holding `len` elements of
+ * `data_type`.  Buffers always hold at least one element, and are
+ * padded out to `wgpu::COPY_BUFFER_ALIGNMENT`.
+ */
+fn buffer_byte_size(data_type: &DataTypeInfo, len: usize)
+  -> usize
+{
+  let align = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+  let size = data_type.array_stride() * usize::max(len, 1);
+  (size + (align - 1)) & !(align - 1)
+}
+
+/**
+ * Check that a buffer of `shape` fits within the limits of `device`,
+ * both as a buffer and as a single binding.  The size is computed
+ * in 128 bits, as the extents of a shape can overflow a `usize`.
+ */
+fn check_buffer_size(
+  device: &wgpu::Device,
+  name: &SharedName,
+  header: Option<&DataTypeInfo>,
+  data_type: &DataTypeInfo,
+  shape: &BufferShape,
+  uniform: bool,
+) -> Result<(), PewterError> {
+  let limits = device.limits();
+  let max_binding = if uniform {
+    limits.max_uniform_buffer_binding_size
+  } else {
+    limits.max_storage_buffer_binding_size
+  };
+  let max_size = u64::min(limits.max_buffer_size, max_binding as u64);
+  let len = shape.extents_3d().iter()
+    .map(|&e| e as u128)
+    .product::<u128>();
+  let offset = header.map_or(0, |header| tail_offset(header, data_type));
+  let align = wgpu::COPY_BUFFER_ALIGNMENT as u128;
+  let size = offset as u128
+           + data_type.array_stride() as u128 * u128::max(len, 1);
+  let size = (size + (align - 1)) & !(align - 1);
+  if size > max_size as u128 {
+    return Err(PewterError::BufferTooLarge {
+      buffer: name.clone(),
+      len: usize::try_from(len).unwrap_or(usize::MAX),
+    });
+  }
+  Ok(())
+}
+
+/**
+ * The name of the type held by a buffer of `data_type` elements,
+ * preceded by a value of `header` if given.
+ */
+pub(crate) fn buffer_type_name(
+  header: Option<&DataTypeInfo>,
+  data_type: &DataTypeInfo,
+) -> String {
+  match header {
+    Some(header) =>
+      format!("{} with array<{}>", header.name(), data_type.name()),
+    None => data_type.name().to_owned(),
+  }
+}
+
+/**
+ * Compute the byte size of a gpu buffer holding `len` elements of
+ * `data_type`, preceded by a value of `header` if given.
+ */
+pub(crate) fn tail_buffer_byte_size(
+  header: Option<&DataTypeInfo>,
+  data_type: &DataTypeInfo,
+  len: usize,
+) -> usize {
+  match header {
+    Some(header) => {
+      let align = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+      let size = tail_offset(header, data_type)
+               + data_type.array_stride() * len;
+      (size + (align - 1)) & !(align - 1)
+    },
+    None => buffer_byte_size(data_type, len),
+  }
+}
+
+/**
+ * Map a `MAP_READ` buffer and copy out its contents.  The device
+ * is polled until the mapping completes.
+ */
+pub(crate) async fn read_mappable_buffer(
+  device: &wgpu::Device,
+  buffer: &wgpu::Buffer,
+) -> Result<Vec<u8>, PewterError> {
+  let slice = buffer.slice(..);
+  let (sender, receiver) = mpsc::channel();
+  slice.map_async(wgpu::MapMode::Read, move |result| {
+    // The receiver outlives the poll below, so ignore send failures.
+    let _ = sender.send(result);
+  });
+  device.poll(wgpu::Maintain::Wait);
+  receiver.recv()
+    .expect("Buffer mapping callback was dropped")
+    .map_err(PewterError::BufferMap) ?;
+  let bytes = slice.get_mapped_range().to_vec();
+  buffer.unmap();
+  Ok(bytes)
+}
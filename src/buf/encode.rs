@@ -0,0 +1,78 @@
+
+use crate::types::{
+  DataType,
+  DataTypeInfo,
+  data_type_info_for,
+  data_type_read,
+  data_type_write,
+};
+
+/**
+ * Encode a slice of values into the byte layout of a gpu array.
+ */
+pub(crate) fn encode_slice<DT: DataType>(values: &[DT], bytes_out: &mut [u8]) {
+  let data_type = data_type_info_for::<DT>();
+  let size = data_type.size();
+  let stride = data_type.array_stride();
+  for (i, value) in values.iter().enumerate() {
+    let offset = i * stride;
+    data_type_write(value, &mut bytes_out[offset .. offset + size]);
+  }
+}
+
+/**
+ * Decode `len` values from the byte layout of a gpu array.
+ */
+pub(crate) fn decode_vec<DT: DataType>(bytes: &[u8], len: usize) -> Vec<DT> {
+  let data_type = data_type_info_for::<DT>();
+  let size = data_type.size();
+  let stride = data_type.array_stride();
+  (0 .. len).map(|i| {
+    let offset = i * stride;
+    let mut value = DT::default();
+    data_type_read(&mut value, &bytes[offset .. offset + size]);
+    value
+  }).collect()
+}
+
+/**
+ * The offset of the runtime-sized array following a header of type
+ * `header`, which is the header's size rounded up to the alignment
+ * of the array's elements, as in a WGSL struct.
+ */
+pub(crate) fn tail_offset(header: &DataTypeInfo, element: &DataTypeInfo)
+  -> usize
+{
+  let align = element.align();
+  (header.size() + (align - 1)) & !(align - 1)
+}
+
+/**
+ * Encode a header followed by a slice of values into the byte layout
+ * of a struct ending in a runtime-sized array.
+ */
+pub(crate) fn encode_tail<H: DataType, DT: DataType>(
+  header: &H,
+  values: &[DT],
+  bytes_out: &mut [u8],
+) {
+  let header_type = data_type_info_for::<H>();
+  let offset = tail_offset(&header_type, &data_type_info_for::<DT>());
+  data_type_write(header, &mut bytes_out[.. header_type.size()]);
+  encode_slice(values, &mut bytes_out[offset ..]);
+}
+
+/**
+ * Decode a header and the first `len` values of the runtime-sized
+ * array following it.
+ */
+pub(crate) fn decode_tail<H: DataType, DT: DataType>(
+  bytes: &[u8],
+  len: usize,
+) -> (H, Vec<DT>) {
+  let header_type = data_type_info_for::<H>();
+  let offset = tail_offset(&header_type, &data_type_info_for::<DT>());
+  let mut header = H::default();
+  data_type_read(&mut header, &bytes[.. header_type.size()]);
+  (header, decode_vec(&bytes[offset ..], len))
+}
@@ -1,6 +1,36 @@
 
+mod access;
+mod shape;
 mod buffer;
+mod typed_buffer;
+mod tail_buffer;
+mod encode;
 
 pub(crate) use self::{
-  buffer::Buffer,
-};
\ No newline at end of file
+  buffer::{
+    Buffer,
+    buffer_type_name,
+    read_mappable_buffer,
+  },
+  encode::{
+    encode_slice,
+    decode_vec,
+    tail_offset,
+    encode_tail,
+    decode_tail,
+  },
+};
+pub use self::{
+  access::{
+    BufferAccess,
+    AccessMode,
+    StorageAccess,
+    WritableAccess,
+    ReadAccess,
+    ReadWriteAccess,
+    UniformAccess,
+  },
+  shape::{BufferShape, BufferCoord},
+  typed_buffer::TypedBuffer,
+  tail_buffer::TailBuffer,
+};
@@ -0,0 +1,70 @@
+
+use crate::types::PrimType;
+
+/**
+ * The dimensionalized size of a buffer: between one and three
+ * extents, with the first extent varying fastest in memory.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BufferShape {
+  dims: usize,
+  extents: [u32; 3],
+}
+impl BufferShape {
+  pub const fn new_1d(x: u32) -> Self {
+    BufferShape { dims: 1, extents: [x, 1, 1] }
+  }
+  pub const fn new_2d(x: u32, y: u32) -> Self {
+    BufferShape { dims: 2, extents: [x, y, 1] }
+  }
+  pub const fn new_3d(x: u32, y: u32, z: u32) -> Self {
+    BufferShape { dims: 3, extents: [x, y, z] }
+  }
+
+  /** The number of dimensions, between 1 and 3. */
+  pub fn dims(&self) -> usize { self.dims }
+
+  /** The extent of each dimension. */
+  pub fn extents(&self) -> &[u32] { &self.extents[.. self.dims] }
+
+  /** The extents padded out to three dimensions with 1s. */
+  pub(crate) fn extents_3d(&self) -> [u32; 3] { self.extents }
+
+  /** The total number of elements. */
+  pub fn len(&self) -> usize {
+    self.extents.iter().map(|&e| e as usize).product()
+  }
+
+  pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+impl From<[u32; 1]> for BufferShape {
+  fn from(extents: [u32; 1]) -> Self {
+    BufferShape::new_1d(extents[0])
+  }
+}
+impl From<[u32; 2]> for BufferShape {
+  fn from(extents: [u32; 2]) -> Self {
+    BufferShape::new_2d(extents[0], extents[1])
+  }
+}
+impl From<[u32; 3]> for BufferShape {
+  fn from(extents: [u32; 3]) -> Self {
+    BufferShape::new_3d(extents[0], extents[1], extents[2])
+  }
+}
+
+/**
+ * The coordinate types used to index 1d, 2d and 3d buffers.
+ */
+pub trait BufferCoord: PrimType {
+  const DIMS: usize;
+}
+impl BufferCoord for u32 {
+  const DIMS: usize = 1;
+}
+impl BufferCoord for [u32; 2] {
+  const DIMS: usize = 2;
+}
+impl BufferCoord for [u32; 3] {
+  const DIMS: usize = 3;
+}
@@ -0,0 +1,79 @@
+use std::marker::PhantomData;
+use crate::{
+  PewterError,
+  types::{DataType, DataTypeInfo, SharedName},
+  buf::{Buffer, BufferShape, encode_tail, decode_tail},
+};
+
+/**
+ * A typed handle to a gpu buffer holding a header value of type `H`,
+ * followed by a runtime-sized array of `DT`.
+ */
+pub struct TailBuffer<H: DataType, DT: DataType> {
+  buffer: Buffer,
+  _dummy: PhantomData<(H, DT)>,
+}
+impl<H: DataType, DT: DataType> TailBuffer<H, DT> {
+  pub(crate) fn new(buffer: Buffer) -> Self {
+    TailBuffer { buffer, _dummy: PhantomData }
+  }
+
+  pub(crate) fn untyped(&self) -> &Buffer { &self.buffer }
+
+  pub fn name(&self) -> &SharedName { self.buffer.name() }
+
+  /** The number of `DT` elements held by the array. */
+  pub fn len(&self) -> usize { self.buffer.len() }
+
+  pub fn is_empty(&self) -> bool { self.buffer.len() == 0 }
+
+  pub fn shape(&self) -> &BufferShape { self.buffer.shape() }
+
+  /** The type of the elements of the array. */
+  pub fn data_type(&self) -> &DataTypeInfo { self.buffer.data_type() }
+
+  /** The type of the header. */
+  pub fn header_type(&self) -> &DataTypeInfo {
+    self.buffer.header().expect("Tail buffer without a header")
+  }
+
+  /**
+   * Encode `header` and `values` into the bytes of the buffer,
+   * checking that the values fit.
+   */
+  pub(crate) fn encode(&self, header: &H, values: &[DT])
+    -> Result<Vec<u8>, PewterError>
+  {
+    if values.len() > self.len() {
+      return Err(PewterError::BufferLengthMismatch {
+        buffer: self.name().clone(),
+        expected: self.len(),
+        actual: values.len(),
+      });
+    }
+    let mut bytes = vec![0_u8; self.buffer.byte_size()];
+    encode_tail(header, values, &mut bytes);
+    Ok(bytes)
+  }
+
+  /**
+   * Write `header`, and `values` into the start of the array.  The
+   * rest of the array is zeroed.
+   */
+  pub fn write(&self, header: &H, values: &[DT]) -> Result<(), PewterError> {
+    let bytes = self.encode(header, values) ?;
+    self.buffer.write_bytes(&bytes);
+    Ok(())
+  }
+
+  /** Read back the header and the contents of the array. */
+  pub async fn read(&self) -> Result<(H, Vec<DT>), PewterError> {
+    let bytes = self.buffer.read_bytes().await ?;
+    Ok(decode_tail(&bytes, self.len()))
+  }
+}
+impl<H: DataType, DT: DataType> Clone for TailBuffer<H, DT> {
+  fn clone(&self) -> Self {
+    TailBuffer { buffer: self.buffer.clone(), _dummy: PhantomData }
+  }
+}
@@ -0,0 +1,66 @@
+
+use std::marker::PhantomData;
+use crate::{
+  PewterError,
+  types::{DataType, DataTypeInfo, SharedName, data_type_info_for},
+  buf::{Buffer, BufferShape, encode_slice, decode_vec},
+};
+
+/**
+ * A typed handle to a gpu buffer holding an array of `DT`.
+ */
+pub struct TypedBuffer<DT: DataType> {
+  buffer: Buffer,
+  _dummy: PhantomData<DT>,
+}
+impl<DT: DataType> TypedBuffer<DT> {
+  pub(crate) fn new(buffer: Buffer) -> Self {
+    TypedBuffer { buffer, _dummy: PhantomData }
+  }
+
+  pub(crate) fn untyped(&self) -> &Buffer { &self.buffer }
+
+  pub fn name(&self) -> &SharedName { self.buffer.name() }
+
+  /** The number of `DT` elements held by the buffer. */
+  pub fn len(&self) -> usize { self.buffer.len() }
+
+  pub fn is_empty(&self) -> bool { self.buffer.len() == 0 }
+
+  pub fn shape(&self) -> &BufferShape { self.buffer.shape() }
+
+  pub fn data_type(&self) -> &DataTypeInfo { self.buffer.data_type() }
+
+  /** Whether this is a uniform buffer, holding a single value. */
+  pub fn is_uniform(&self) -> bool { self.buffer.is_uniform() }
+
+  /** Write `values` into the start of the buffer. */
+  pub fn write_slice(&self, values: &[DT]) -> Result<(), PewterError> {
+    if values.len() > self.len() {
+      return Err(PewterError::BufferLengthMismatch {
+        buffer: self.name().clone(),
+        expected: self.len(),
+        actual: values.len(),
+      });
+    }
+    if values.is_empty() {
+      return Ok(());
+    }
+    let stride = data_type_info_for::<DT>().array_stride();
+    let mut bytes = vec![0_u8; values.len() * stride];
+    encode_slice(values, &mut bytes);
+    self.buffer.write_bytes(&bytes);
+    Ok(())
+  }
+
+  /** Read back the contents of the buffer. */
+  pub async fn read_to_vec(&self) -> Result<Vec<DT>, PewterError> {
+    let bytes = self.buffer.read_bytes().await ?;
+    Ok(decode_vec(&bytes, self.len()))
+  }
+}
+impl<DT: DataType> Clone for TypedBuffer<DT> {
+  fn clone(&self) -> Self {
+    TypedBuffer { buffer: self.buffer.clone(), _dummy: PhantomData }
+  }
+}
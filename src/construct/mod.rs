@@ -0,0 +1,20 @@
+
+/*!
+ * This module defines the runtime side of constructs: the steps
+ * recorded by a `ConstructSession`, and the outputs read back
+ * when a construct is performed.
+ */
+
+/** Recorded construct steps. */
+mod step;
+
+/** Values read back from a performed construct. */
+mod outputs;
+
+pub(crate) use self::{
+  step::{ConstructStep, DispatchStep, DispatchSlot},
+  outputs::ConstructResultFn,
+};
+pub use self::{
+  outputs::{ConstructOutputs, ConstructReadback, ConstructTailReadback},
+};
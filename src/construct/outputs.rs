@@ -0,0 +1,72 @@
+
+use std::marker::PhantomData;
+use crate::{
+  types::DataType,
+  buf::{decode_vec, decode_tail},
+};
+
+/**
+ * A handle to the contents of a buffer, as read back at a
+ * particular point in a construct.
+ */
+pub struct ConstructReadback<DT: DataType> {
+  idx: usize,
+  len: usize,
+  _dummy: PhantomData<DT>,
+}
+impl<DT: DataType> ConstructReadback<DT> {
+  pub(crate) fn new(idx: usize, len: usize) -> Self {
+    ConstructReadback { idx, len, _dummy: PhantomData }
+  }
+}
+impl<DT: DataType> Clone for ConstructReadback<DT> {
+  fn clone(&self) -> Self { *self }
+}
+impl<DT: DataType> Copy for ConstructReadback<DT> {}
+
+/**
+ * A handle to the header and array of a tail buffer, as read back at
+ * a particular point in a construct.
+ */
+pub struct ConstructTailReadback<H: DataType, DT: DataType> {
+  idx: usize,
+  len: usize,
+  _dummy: PhantomData<(H, DT)>,
+}
+impl<H: DataType, DT: DataType> ConstructTailReadback<H, DT> {
+  pub(crate) fn new(idx: usize, len: usize) -> Self {
+    ConstructTailReadback { idx, len, _dummy: PhantomData }
+  }
+}
+impl<H: DataType, DT: DataType> Clone for ConstructTailReadback<H, DT> {
+  fn clone(&self) -> Self { *self }
+}
+impl<H: DataType, DT: DataType> Copy for ConstructTailReadback<H, DT> {}
+
+/** Computes the result of a construct from its outputs. */
+pub(crate) type ConstructResultFn<T> = Box<dyn Fn (&ConstructOutputs) -> T>;
+
+/**
+ * The buffer contents read back by a performed construct, from
+ * which the construct's result is computed.
+ */
+pub struct ConstructOutputs {
+  readbacks: Vec<Vec<u8>>,
+}
+impl ConstructOutputs {
+  pub(crate) fn new(readbacks: Vec<Vec<u8>>) -> Self {
+    ConstructOutputs { readbacks }
+  }
+
+  pub fn get<DT: DataType>(&self, readback: ConstructReadback<DT>)
+    -> Vec<DT>
+  {
+    decode_vec(&self.readbacks[readback.idx], readback.len)
+  }
+
+  pub fn get_tail<H: DataType, DT: DataType>(&self,
+    readback: ConstructTailReadback<H, DT>,
+  ) -> (H, Vec<DT>) {
+    decode_tail(&self.readbacks[readback.idx], readback.len)
+  }
+}
@@ -0,0 +1,231 @@
+
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+use crate::{
+  types::{SharedName, DataTypeInfo},
+  buf::{Buffer, BufferAccess},
+  declare::DeclareBinding,
+};
+
+/**
+ * A single step of a construct.  Steps are encoded into one
+ * command buffer, in the order they were recorded.
+ */
+pub(crate) enum ConstructStep {
+  Write { buffer: Buffer, bytes: Vec<u8> },
+  Dispatch(DispatchStep),
+  Read { buffer: Buffer },
+}
+impl ConstructStep {
+  /**
+   * Encode the step.  Read steps copy into a new staging buffer,
+   * which is appended to `staging`.
+   */
+  pub(crate) fn encode(&self,
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    staging: &mut Vec<wgpu::Buffer>,
+  ) {
+    match *self {
+      ConstructStep::Write { ref buffer, ref bytes } => {
+        let upload = device.create_buffer_init(
+          &wgpu::util::BufferInitDescriptor {
+            label: Some("pewter_upload"),
+            contents: bytes,
+            usage: wgpu::BufferUsages::COPY_SRC,
+          }
+        );
+        encoder.copy_buffer_to_buffer(
+          &upload, 0,
+          buffer.wgpu_buffer(), 0,
+          bytes.len() as wgpu::BufferAddress,
+        );
+      },
+      ConstructStep::Dispatch(ref dispatch) => {
+        dispatch.encode(encoder);
+      },
+      ConstructStep::Read { ref buffer } => {
+        staging.push(buffer.encode_download(encoder));
+      },
+    }
+  }
+}
+
+/**
+ * A buffer slot declared by a shader file.
+ */
+pub(crate) struct DispatchSlot {
+  name: SharedName,
+  data_type: DataTypeInfo,
+  access: BufferAccess,
+  dims: usize,
+  header: Option<DataTypeInfo>,
+}
+impl DispatchSlot {
+  pub(crate) fn new(
+    name: SharedName,
+    data_type: DataTypeInfo,
+    access: BufferAccess,
+    dims: usize,
+    header: Option<DataTypeInfo>,
+  ) -> Self {
+    DispatchSlot { name, data_type, access, dims, header }
+  }
+
+  pub(crate) fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+  pub(crate) fn access(&self) -> BufferAccess { self.access }
+  pub(crate) fn dims(&self) -> usize { self.dims }
+  pub(crate) fn header(&self) -> Option<&DataTypeInfo> {
+    self.header.as_ref()
+  }
+}
+
+/**
+ * The dispatch of an entrypoint over a grid.  The buffer slots
+ * are bound while the construct session is running, and the
+ * bind groups are created when it completes.
+ */
+pub(crate) struct DispatchStep {
+  entry: SharedName,
+  pipeline: Arc<wgpu::ComputePipeline>,
+  slots: Vec<DispatchSlot>,
+  bound: Vec<Option<Buffer>>,
+  grid_buffer: wgpu::Buffer,
+  workgroups: [u32; 3],
+  bind_groups: Vec<wgpu::BindGroup>,
+}
+impl DispatchStep {
+  pub(crate) fn new(
+    device: &wgpu::Device,
+    entry: SharedName,
+    pipeline: Arc<wgpu::ComputePipeline>,
+    slots: Vec<DispatchSlot>,
+    grid: [u32; 3],
+    workgroup_size: [u32; 3],
+  ) -> Self {
+    let bound = slots.iter().map(|_| None).collect();
+    let mut contents = [0_u8; 16];
+    let mut workgroups = [0_u32; 3];
+    for i in 0 .. 3 {
+      contents[i * 4 .. (i + 1) * 4].copy_from_slice(&grid[i].to_le_bytes());
+      workgroups[i] = grid[i].div_ceil(workgroup_size[i]);
+    }
+    let grid_buffer = device.create_buffer_init(
+      &wgpu::util::BufferInitDescriptor {
+        label: Some("pewter_grid_size"),
+        contents: &contents,
+        usage: wgpu::BufferUsages::UNIFORM,
+      }
+    );
+    let bind_groups = Vec::new();
+    DispatchStep {
+      entry, pipeline, slots, bound, grid_buffer, workgroups, bind_groups
+    }
+  }
+
+  pub(crate) fn entry(&self) -> &SharedName { &self.entry }
+
+  pub(crate) fn slot_index(&self, name: &str) -> Option<usize> {
+    self.slots.iter().position(|s| s.name().as_ref() == name)
+  }
+  pub(crate) fn slot(&self, idx: usize) -> &DispatchSlot {
+    &self.slots[idx]
+  }
+
+  pub(crate) fn bind(&mut self, idx: usize, buffer: Buffer) {
+    self.bound[idx] = Some(buffer);
+  }
+
+  /**
+   * The name of another slot already holding `buffer` with a
+   * different access than slot `idx`.  A buffer can't be bound as
+   * both read-only and read-write in a single dispatch.
+   */
+  pub(crate) fn conflicting_slot(&self, idx: usize, buffer: &Buffer)
+    -> Option<&SharedName>
+  {
+    let access = self.slots[idx].access();
+    self.slots.iter().zip(self.bound.iter()).enumerate()
+      .find(|&(i, (slot, bound))| {
+        i != idx && slot.access() != access &&
+          bound.as_ref().is_some_and(|b| b.same_buffer(buffer))
+      })
+      .map(|(_, (slot, _))| slot.name())
+  }
+
+  /** The name of the first slot with no buffer bound to it. */
+  pub(crate) fn first_unbound(&self) -> Option<&SharedName> {
+    self.slots.iter().zip(self.bound.iter())
+      .find(|(_, b)| b.is_none())
+      .map(|(s, _)| s.name())
+  }
+
+  /**
+   * Create the bind groups: the bound buffers in group 0, and the
+   * grid size and the extents of each bound buffer in group 1.
+   */
+  pub(crate) fn create_bind_groups(&mut self, device: &wgpu::Device) {
+    let entries = self.bound.iter().enumerate()
+      .map(|(i, buf)| wgpu::BindGroupEntry {
+        binding: DeclareBinding::for_buffer(i).binding(),
+        resource: buf.as_ref()
+          .expect("Unbound buffer slot")
+          .wgpu_buffer()
+          .as_entire_binding(),
+      })
+      .collect::<Vec<_>>();
+    let buffers_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some(self.entry.as_ref()),
+      layout: &self.pipeline.get_bind_group_layout(
+        DeclareBinding::BUFFER_GROUP),
+      entries: &entries,
+    });
+    let mut uniform_entries = vec![wgpu::BindGroupEntry {
+      binding: DeclareBinding::grid_size().binding(),
+      resource: self.grid_buffer.as_entire_binding(),
+    }];
+    let shapes_buffer;
+    if !self.bound.is_empty() {
+      let mut contents = vec![0_u8; self.bound.len() * 16];
+      for (i, buf) in self.bound.iter().enumerate() {
+        let extents = buf.as_ref().expect("Unbound buffer slot")
+          .shape().extents_3d();
+        for (j, extent) in extents.iter().enumerate() {
+          let offset = i * 16 + j * 4;
+          contents[offset .. offset + 4].copy_from_slice(&extent.to_le_bytes());
+        }
+      }
+      shapes_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+          label: Some("pewter_buffer_shapes"),
+          contents: &contents,
+          usage: wgpu::BufferUsages::UNIFORM,
+        }
+      );
+      uniform_entries.push(wgpu::BindGroupEntry {
+        binding: DeclareBinding::buffer_shapes().binding(),
+        resource: shapes_buffer.as_entire_binding(),
+      });
+    }
+    let grid_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some(self.entry.as_ref()),
+      layout: &self.pipeline.get_bind_group_layout(
+        DeclareBinding::UNIFORM_GROUP),
+      entries: &uniform_entries,
+    });
+    self.bind_groups = vec![buffers_group, grid_group];
+  }
+
+  fn encode(&self, encoder: &mut wgpu::CommandEncoder) {
+    let mut pass = encoder.begin_compute_pass(
+      &wgpu::ComputePassDescriptor { label: Some(self.entry.as_ref()) }
+    );
+    pass.set_pipeline(&self.pipeline);
+    for (i, group) in self.bind_groups.iter().enumerate() {
+      pass.set_bind_group(i as u32, group, &[]);
+    }
+    let [x, y, z] = self.workgroups;
+    pass.dispatch_workgroups(x, y, z);
+  }
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+/**
+ * The group and binding of a resource used by a shader file.
+ *
+ * Buffers occupy group 0, in the order they are declared.  The
+ * uniforms pewter provides itself occupy group 1: the grid size at
+ * binding 0, and the buffer shapes at binding 1.  The same
+ * assignment is used to generate the WGSL and to lay out the bind
+ * groups at runtime.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DeclareBinding {
+  group: u32,
+  binding: u32,
+}
+impl DeclareBinding {
+  pub(crate) const BUFFER_GROUP: u32 = 0;
+  pub(crate) const UNIFORM_GROUP: u32 = 1;
+  pub(crate) const NUM_GROUPS: usize = 2;
+
+  pub(crate) fn for_buffer(idx: usize) -> Self {
+    DeclareBinding { group: Self::BUFFER_GROUP, binding: idx as u32 }
+  }
+  pub(crate) fn grid_size() -> Self {
+    DeclareBinding { group: Self::UNIFORM_GROUP, binding: 0 }
+  }
+  pub(crate) fn buffer_shapes() -> Self {
+    DeclareBinding { group: Self::UNIFORM_GROUP, binding: 1 }
+  }
+
+  pub(crate) fn group(&self) -> u32 { self.group }
+  pub(crate) fn binding(&self) -> u32 { self.binding }
+
+  pub(crate) fn to_text<W>(self, out: &mut W) -> fmt::Result
+    where W: fmt::Write
+  {
+    writeln!(out, "@group({}) @binding({})", self.group, self.binding)
+  }
+}
@@ -1,16 +1,148 @@
-use crate::types::{SharedName, DataTypeInfo};
+use std::fmt;
+use crate::{
+  types::{SharedName, DataTypeInfo},
+  buf::BufferAccess,
+  declare::DeclareBinding,
+};
 
 /**
- * A declared buffer, which carries only a name and type.
+ * A declared buffer, which carries a name, type, access mode and
+ * number of dimensions.  The extents of each dimension are only
+ * known once a buffer is bound to it.
+ *
+ * A tail buffer holds a struct of a header value followed by a
+ * runtime-sized array, `{ header: H, items: array<T> }`.
  */
 pub(crate) struct DeclareBuffer {
   name: SharedName,
   data_type: DataTypeInfo,
+  access: BufferAccess,
+  dims: usize,
+
+  // The type of the value preceding the array, for tail buffers.
+  header: Option<DataTypeInfo>,
 }
 impl DeclareBuffer {
-  pub(crate) fn new(name: SharedName, data_type: DataTypeInfo) -> Self {
-    DeclareBuffer { name, data_type }
+  pub(crate) fn new(
+    name: SharedName,
+    data_type: DataTypeInfo,
+    access: BufferAccess,
+    dims: usize,
+  ) -> Self {
+    DeclareBuffer { name, data_type, access, dims, header: None }
+  }
+
+  pub(crate) fn new_tail(
+    name: SharedName,
+    header: DataTypeInfo,
+    data_type: DataTypeInfo,
+    access: BufferAccess,
+  ) -> Self {
+    let header = Some(header);
+    DeclareBuffer { name, data_type, access, dims: 1, header }
   }
 
   pub(crate) fn name(&self) -> &SharedName { &self.name }
-}
\ No newline at end of file
+  pub(crate) fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+  pub(crate) fn access(&self) -> BufferAccess { self.access }
+  pub(crate) fn dims(&self) -> usize { self.dims }
+  pub(crate) fn header(&self) -> Option<&DataTypeInfo> {
+    self.header.as_ref()
+  }
+
+  /** The types held by the buffer, including the header's. */
+  pub(crate) fn data_types(&self) -> impl Iterator<Item = &DataTypeInfo> {
+    self.header.iter().chain(Some(&self.data_type))
+  }
+
+  /** The name of the WGSL struct held by a tail buffer. */
+  pub(crate) fn tail_struct_name(name: &SharedName) -> String {
+    format!("x_tail_{}", name.as_ref())
+  }
+
+  /** The WGSL expression naming the header of a tail buffer. */
+  pub(crate) fn header_path(name: &SharedName) -> String {
+    format!("{}.header", name.as_ref())
+  }
+
+  /** The WGSL expression naming the array of a tail buffer. */
+  pub(crate) fn items_path(name: &SharedName) -> String {
+    format!("{}.items", name.as_ref())
+  }
+
+  /** The name of the WGSL helper linearizing a coordinate. */
+  pub(crate) fn index_helper_name(name: &SharedName) -> String {
+    format!("x_index_{}", name.as_ref())
+  }
+
+  pub(crate) fn to_text<W>(&self, out: &mut W, binding: DeclareBinding)
+    -> fmt::Result
+    where W: fmt::Write
+  {
+    if let Some(ref header) = self.header {
+      return self.tail_to_text(out, binding, header);
+    }
+    binding.to_text(out) ?;
+    match self.access {
+      BufferAccess::Read =>
+        writeln!(out, "var<storage, read> {}: array<{}>;",
+          self.name.as_ref(), self.data_type.wgsl_name()),
+      BufferAccess::ReadWrite =>
+        writeln!(out, "var<storage, read_write> {}: array<{}>;",
+          self.name.as_ref(), self.data_type.wgsl_name()),
+      BufferAccess::Uniform =>
+        writeln!(out, "var<uniform> {}: {};",
+          self.name.as_ref(), self.data_type.wgsl_name()),
+    }
+  }
+
+  fn tail_to_text<W>(&self,
+    out: &mut W,
+    binding: DeclareBinding,
+    header: &DataTypeInfo,
+  ) -> fmt::Result
+    where W: fmt::Write
+  {
+    let struct_name = DeclareBuffer::tail_struct_name(&self.name);
+    writeln!(out, "struct {} {{", struct_name) ?;
+    writeln!(out, "  header: {},", header.wgsl_name()) ?;
+    writeln!(out, "  items: array<{}>,", self.data_type.wgsl_name()) ?;
+    writeln!(out, "}}") ?;
+    binding.to_text(out) ?;
+    let access = match self.access {
+      BufferAccess::Read => "read",
+      BufferAccess::ReadWrite => "read_write",
+      BufferAccess::Uniform =>
+        unreachable!("Uniform buffers can't hold runtime-sized arrays"),
+    };
+    writeln!(out, "var<storage, {}> {}: {};",
+      access, self.name.as_ref(), struct_name)
+  }
+
+  /**
+   * Write the helper function mapping a coordinate to an index into
+   * this buffer, using the extents in `x_buffer_shapes[binding]`.
+   */
+  pub(crate) fn index_helper_to_text<W>(&self, out: &mut W, binding: usize)
+    -> fmt::Result
+    where W: fmt::Write
+  {
+    let helper = DeclareBuffer::index_helper_name(&self.name);
+    match self.dims {
+      2 => {
+        writeln!(out, "fn {}(coord: vec2<u32>) -> u32 {{", helper) ?;
+        writeln!(out, "  let shape = x_buffer_shapes[{}];", binding) ?;
+        writeln!(out, "  return coord.y * shape.x + coord.x;") ?;
+        writeln!(out, "}}")
+      },
+      3 => {
+        writeln!(out, "fn {}(coord: vec3<u32>) -> u32 {{", helper) ?;
+        writeln!(out, "  let shape = x_buffer_shapes[{}];", binding) ?;
+        writeln!(out,
+          "  return (coord.z * shape.y + coord.y) * shape.x + coord.x;") ?;
+        writeln!(out, "}}")
+      },
+      _ => Ok(()),
+    }
+  }
+}
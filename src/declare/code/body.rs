@@ -1,4 +1,6 @@
+use std::fmt;
 use crate::{
+  types::DataTypeInfo,
   declare::DeclareCodeStatement
 };
 
@@ -7,6 +9,7 @@ use crate::{
  * The declaration of a code body, consisting of a sequence
  * of statements.
  */
+#[derive(Clone)]
 pub(crate) struct DeclareCodeBody {
   statements: Vec<DeclareCodeStatement>,
 }
@@ -19,7 +22,28 @@ impl DeclareCodeBody {
     self.statements.push(stmt)
   }
 
-  pub(crate) fn statements(&self) -> &[DeclareCodeStatement] {
-    &self.statements
+  pub(crate) fn last_statement_mut(&mut self)
+    -> Option<&mut DeclareCodeStatement>
+  {
+    self.statements.last_mut()
   }
-}
\ No newline at end of file
+
+  /** Add the types which must be declared for the body to `out`. */
+  pub(crate) fn collect_data_types<'a>(&'a self,
+    out: &mut Vec<&'a DataTypeInfo>
+  ) {
+    for stmt in self.statements.iter() {
+      stmt.collect_data_types(out);
+    }
+  }
+
+  /** Write the statements, each indented to `depth` levels. */
+  pub(crate) fn to_text<W>(&self, out: &mut W, depth: usize) -> fmt::Result
+    where W: fmt::Write
+  {
+    for stmt in self.statements.iter() {
+      stmt.to_text(out, depth) ?;
+    }
+    Ok(())
+  }
+}
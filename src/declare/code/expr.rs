@@ -1,5 +1,6 @@
 use std::fmt;
-use crate::types::SharedName;
+use crate::types::{DataTypeInfo, NonAtomic, PrimTypeInfo, SharedName};
+use super::{DeclareBinaryOp, DeclareUnaryOp, DeclareLiteral};
 
 
 /**
@@ -8,34 +9,222 @@ use crate::types::SharedName;
 #[derive(Clone)]
 pub(crate) enum DeclareCodeExpr {
   Argument { name: SharedName },
-  Add { lhs: Box<DeclareCodeExpr>, rhs: Box<DeclareCodeExpr> }
+  Binary {
+    op: DeclareBinaryOp,
+    lhs: Box<DeclareCodeExpr>,
+    rhs: Box<DeclareCodeExpr>,
+  },
+  Unary { op: DeclareUnaryOp, expr: Box<DeclareCodeExpr> },
+  // A value conversion, such as `f32(x)`.
+  Convert { to: PrimTypeInfo, expr: Box<DeclareCodeExpr> },
+  // A reinterpretation of the bits of a value, `bitcast<T>(x)`.
+  Bitcast { to: PrimTypeInfo, expr: Box<DeclareCodeExpr> },
+  // A vector built from components, such as `vec3<f32>(a, b, c)`.
+  Construct { to: PrimTypeInfo, args: Vec<DeclareCodeExpr> },
+  // Components of a vector, by index: `v.x` or `v.zyx`.
+  Swizzle { expr: Box<DeclareCodeExpr>, components: Vec<usize> },
+  Call { name: SharedName, args: Vec<DeclareCodeExpr> },
+  Builtin { name: &'static str, args: Vec<DeclareCodeExpr> },
+  Global { name: SharedName },
+  Variable { name: SharedName },
+  Literal(DeclareLiteral),
+  Index { buffer: SharedName, index: Box<DeclareCodeExpr> },
+  // An element of an array value.
+  Element { base: Box<DeclareCodeExpr>, index: Box<DeclareCodeExpr> },
 }
 impl DeclareCodeExpr {
   pub(crate) fn new_argument(name: SharedName) -> Self {
     DeclareCodeExpr::Argument { name }
   }
 
-  pub(crate) fn new_add(
+  pub(crate) fn new_binary(
+    op: DeclareBinaryOp,
     lhs: Box<DeclareCodeExpr>,
     rhs: Box<DeclareCodeExpr>,
   ) -> Self {
-    DeclareCodeExpr::Add { lhs, rhs }
+    DeclareCodeExpr::Binary { op, lhs, rhs }
+  }
+
+  pub(crate) fn new_unary(op: DeclareUnaryOp, expr: Box<DeclareCodeExpr>)
+    -> Self
+  {
+    DeclareCodeExpr::Unary { op, expr }
+  }
+
+  pub(crate) fn new_convert(to: PrimTypeInfo, expr: Box<DeclareCodeExpr>)
+    -> Self
+  {
+    DeclareCodeExpr::Convert { to, expr }
+  }
+
+  pub(crate) fn new_bitcast(to: PrimTypeInfo, expr: Box<DeclareCodeExpr>)
+    -> Self
+  {
+    DeclareCodeExpr::Bitcast { to, expr }
+  }
+
+  pub(crate) fn new_construct(to: PrimTypeInfo, args: Vec<DeclareCodeExpr>)
+    -> Self
+  {
+    DeclareCodeExpr::Construct { to, args }
+  }
+
+  pub(crate) fn new_swizzle(
+    expr: Box<DeclareCodeExpr>,
+    components: Vec<usize>,
+  ) -> Self {
+    DeclareCodeExpr::Swizzle { expr, components }
+  }
+
+  pub(crate) fn new_call(
+    name: SharedName,
+    args: Vec<DeclareCodeExpr>,
+  ) -> Self {
+    DeclareCodeExpr::Call { name, args }
+  }
+
+  pub(crate) fn new_builtin(
+    name: &'static str,
+    args: Vec<DeclareCodeExpr>,
+  ) -> Self {
+    DeclareCodeExpr::Builtin { name, args }
+  }
+
+  pub(crate) fn new_global(name: SharedName) -> Self {
+    DeclareCodeExpr::Global { name }
+  }
+
+  pub(crate) fn new_variable(name: SharedName) -> Self {
+    DeclareCodeExpr::Variable { name }
+  }
+
+  pub(crate) fn new_literal<DT: NonAtomic>(value: &DT) -> Self {
+    DeclareCodeExpr::Literal(DeclareLiteral::new(value))
+  }
+
+  pub(crate) fn new_index(
+    buffer: SharedName,
+    index: Box<DeclareCodeExpr>,
+  ) -> Self {
+    DeclareCodeExpr::Index { buffer, index }
+  }
+
+  pub(crate) fn new_element(
+    base: Box<DeclareCodeExpr>,
+    index: Box<DeclareCodeExpr>,
+  ) -> Self {
+    DeclareCodeExpr::Element { base, index }
+  }
+
+  /**
+   * Add the types which must be declared for this expression, such
+   * as those of record literals, to `out`.
+   */
+  pub(crate) fn collect_data_types<'a>(&'a self,
+    out: &mut Vec<&'a DataTypeInfo>
+  ) {
+    match *self {
+      DeclareCodeExpr::Binary { ref lhs, ref rhs, .. } => {
+        lhs.collect_data_types(out);
+        rhs.collect_data_types(out);
+      },
+      DeclareCodeExpr::Unary { ref expr, .. } |
+      DeclareCodeExpr::Convert { ref expr, .. } |
+      DeclareCodeExpr::Bitcast { ref expr, .. } |
+      DeclareCodeExpr::Swizzle { ref expr, .. } =>
+        expr.collect_data_types(out),
+      DeclareCodeExpr::Construct { ref args, .. } |
+      DeclareCodeExpr::Call { ref args, .. } |
+      DeclareCodeExpr::Builtin { ref args, .. } => {
+        for arg in args.iter() {
+          arg.collect_data_types(out);
+        }
+      },
+      DeclareCodeExpr::Literal(ref literal) => out.push(literal.data_type()),
+      DeclareCodeExpr::Index { ref index, .. } =>
+        index.collect_data_types(out),
+      DeclareCodeExpr::Element { ref base, ref index } => {
+        base.collect_data_types(out);
+        index.collect_data_types(out);
+      },
+      DeclareCodeExpr::Argument { .. } |
+      DeclareCodeExpr::Global { .. } |
+      DeclareCodeExpr::Variable { .. } => {},
+    }
   }
 
   pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
     where W: fmt::Write
   {
-    match self {
-      &DeclareCodeExpr::Argument{ ref name } => {
+    match *self {
+      DeclareCodeExpr::Argument{ ref name } => {
         write!(out, "{}", name.as_ref() )
       },
-      &DeclareCodeExpr::Add { ref lhs, ref rhs } => {
+      DeclareCodeExpr::Binary { op, ref lhs, ref rhs } => {
         write!(out, "(") ?;
         lhs.to_text(out) ?;
-        write!(out, " + ") ?;
+        write!(out, " {} ", op.symbol()) ?;
         rhs.to_text(out) ?;
         write!(out, ")")
-      }
+      },
+      DeclareCodeExpr::Unary { op, ref expr } => {
+        write!(out, "({}", op.symbol()) ?;
+        expr.to_text(out) ?;
+        write!(out, ")")
+      },
+      DeclareCodeExpr::Convert { to, ref expr } => {
+        write!(out, "{}(", to.name()) ?;
+        expr.to_text(out) ?;
+        write!(out, ")")
+      },
+      DeclareCodeExpr::Bitcast { to, ref expr } => {
+        write!(out, "bitcast<{}>(", to.name()) ?;
+        expr.to_text(out) ?;
+        write!(out, ")")
+      },
+      DeclareCodeExpr::Construct { to, ref args } =>
+        call_to_text(out, to.name(), args),
+      DeclareCodeExpr::Swizzle { ref expr, ref components } => {
+        expr.to_text(out) ?;
+        write!(out, ".") ?;
+        for &idx in components.iter() {
+          write!(out, "{}", &"xyzw"[idx ..= idx]) ?;
+        }
+        Ok(())
+      },
+      DeclareCodeExpr::Call { ref name, ref args } =>
+        call_to_text(out, name.as_ref(), args),
+      DeclareCodeExpr::Builtin { name, ref args } =>
+        call_to_text(out, name, args),
+      DeclareCodeExpr::Global { ref name } => {
+        write!(out, "{}", name.as_ref())
+      },
+      DeclareCodeExpr::Variable { ref name } => {
+        write!(out, "{}", name.as_ref())
+      },
+      DeclareCodeExpr::Literal(ref literal) => literal.to_text(out),
+      DeclareCodeExpr::Index { ref buffer, ref index } => {
+        write!(out, "{}[", buffer.as_ref()) ?;
+        index.to_text(out) ?;
+        write!(out, "]")
+      },
+      DeclareCodeExpr::Element { ref base, ref index } => {
+        base.to_text(out) ?;
+        write!(out, "[") ?;
+        index.to_text(out) ?;
+        write!(out, "]")
+      },
     }
   }
-}
\ No newline at end of file
+}
+fn call_to_text<W>(out: &mut W, name: &str, args: &[DeclareCodeExpr])
+  -> fmt::Result
+  where W: fmt::Write
+{
+  write!(out, "{}(", name) ?;
+  for (i, arg) in args.iter().enumerate() {
+    if i > 0 { write!(out, ", ") ?; }
+    arg.to_text(out) ?;
+  }
+  write!(out, ")")
+}
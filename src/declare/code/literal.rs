@@ -0,0 +1,118 @@
+use std::fmt;
+use crate::types::{
+  DataTypeInfo,
+  NonAtomic,
+  PrimTypeInfo,
+  ScalarTypeInfo,
+  VectorTypeInfo,
+  data_type_info_for,
+  data_type_write,
+};
+
+
+/**
+ * A constant value written into shader code.  The value is held in
+ * its GPU byte layout, and written out as WGSL from that.
+ */
+#[derive(Clone)]
+pub(crate) struct DeclareLiteral {
+  data_type: DataTypeInfo,
+  bytes: Vec<u8>,
+}
+impl DeclareLiteral {
+  pub(crate) fn new<DT: NonAtomic>(value: &DT) -> Self {
+    let data_type = data_type_info_for::<DT>();
+    let mut bytes = vec![0; data_type.size()];
+    data_type_write(value, &mut bytes);
+    DeclareLiteral { data_type, bytes }
+  }
+
+  pub(crate) fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+
+  pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
+    where W: fmt::Write
+  {
+    value_to_text(out, &self.data_type, &self.bytes)
+  }
+}
+
+/* Write the value of type `data_type` held in `bytes`. */
+fn value_to_text<W>(out: &mut W, data_type: &DataTypeInfo, bytes: &[u8])
+  -> fmt::Result
+  where W: fmt::Write
+{
+  match *data_type {
+    DataTypeInfo::Prim(PrimTypeInfo::Scalar(scalar)) =>
+      scalar_to_text(out, scalar, bytes),
+    DataTypeInfo::Prim(PrimTypeInfo::Vector(vector)) =>
+      vector_to_text(out, vector, bytes),
+    DataTypeInfo::Prim(PrimTypeInfo::Atomic(_)) =>
+      unreachable!("Literals can't be atomic, or hold atomics"),
+    DataTypeInfo::Prim(PrimTypeInfo::Matrix(matrix)) => {
+      write!(out, "{}(", matrix.name()) ?;
+      let stride = matrix.column_stride();
+      for i in 0 .. matrix.columns() {
+        if i > 0 { write!(out, ", ") ?; }
+        vector_to_text(out, matrix.column(), &bytes[i * stride ..]) ?;
+      }
+      write!(out, ")")
+    },
+    DataTypeInfo::Record(ref record_type) => {
+      write!(out, "{}(", record_type.wgsl_name().as_ref()) ?;
+      for (i, field) in record_type.record_info().fields().iter().enumerate() {
+        if i > 0 { write!(out, ", ") ?; }
+        value_to_text(out, field.data_type(), &bytes[field.offset() ..]) ?;
+      }
+      write!(out, ")")
+    },
+    DataTypeInfo::Array(ref array) => {
+      write!(out, "{}(", array.wgsl_name().as_ref()) ?;
+      let stride = array.stride();
+      for i in 0 .. array.num_elements() {
+        if i > 0 { write!(out, ", ") ?; }
+        value_to_text(out, array.element(), &bytes[i * stride ..]) ?;
+      }
+      write!(out, ")")
+    },
+  }
+}
+
+fn vector_to_text<W>(out: &mut W, vector: VectorTypeInfo, bytes: &[u8])
+  -> fmt::Result
+  where W: fmt::Write
+{
+  write!(out, "{}(", vector.name()) ?;
+  let size = vector.component().size();
+  for i in 0 .. vector.num_components() {
+    if i > 0 { write!(out, ", ") ?; }
+    scalar_to_text(out, vector.component(), &bytes[i * size ..]) ?;
+  }
+  write!(out, ")")
+}
+
+fn scalar_to_text<W>(out: &mut W, scalar: ScalarTypeInfo, bytes: &[u8])
+  -> fmt::Result
+  where W: fmt::Write
+{
+  let mut word: [u8; 4] = Default::default();
+  word.copy_from_slice(&bytes[.. 4]);
+  let bits = u32::from_le_bytes(word);
+  match scalar {
+    ScalarTypeInfo::U32 => write!(out, "{}u", bits),
+    ScalarTypeInfo::I32 => match bits as i32 {
+      // Negative literals are parenthesized so that they can follow
+      // another `-` without forming `--`.
+      value if value < 0 => write!(out, "({}i)", value),
+      value => write!(out, "{}i", value),
+    },
+    ScalarTypeInfo::F32 => match f32::from_bits(bits) {
+      // NaN and the infinities have no literal syntax.
+      value if !value.is_finite() =>
+        write!(out, "bitcast<f32>({}u)", bits),
+      // `Debug` writes the shortest text that reads back exactly.
+      value if value.is_sign_negative() => write!(out, "({:?}f)", value),
+      value => write!(out, "{:?}f", value),
+    },
+    ScalarTypeInfo::Bool => write!(out, "{}", bits != 0),
+  }
+}
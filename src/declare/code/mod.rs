@@ -8,8 +8,16 @@ mod statement;
 /** Declaration of a code expression. */
 mod expr;
 
+/** Operators used in code expressions. */
+mod op;
+
+/** Constant values in code expressions. */
+mod literal;
+
 pub(crate) use self::{
   body::DeclareCodeBody,
   statement::DeclareCodeStatement,
   expr::DeclareCodeExpr,
+  op::{DeclareBinaryOp, DeclareUnaryOp},
+  literal::DeclareLiteral,
 };
\ No newline at end of file
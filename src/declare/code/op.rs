@@ -0,0 +1,71 @@
+
+/**
+ * An operator applied to two expressions.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DeclareBinaryOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+  BitAnd,
+  BitOr,
+  BitXor,
+  Shl,
+  Shr,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Eq,
+  Ne,
+  And,
+  Or,
+}
+impl DeclareBinaryOp {
+  pub(crate) fn symbol(&self) -> &'static str {
+    match *self {
+      DeclareBinaryOp::Add => "+",
+      DeclareBinaryOp::Sub => "-",
+      DeclareBinaryOp::Mul => "*",
+      DeclareBinaryOp::Div => "/",
+      DeclareBinaryOp::Rem => "%",
+      DeclareBinaryOp::BitAnd => "&",
+      DeclareBinaryOp::BitOr => "|",
+      DeclareBinaryOp::BitXor => "^",
+      DeclareBinaryOp::Shl => "<<",
+      DeclareBinaryOp::Shr => ">>",
+      DeclareBinaryOp::Lt => "<",
+      DeclareBinaryOp::Le => "<=",
+      DeclareBinaryOp::Gt => ">",
+      DeclareBinaryOp::Ge => ">=",
+      DeclareBinaryOp::Eq => "==",
+      DeclareBinaryOp::Ne => "!=",
+      DeclareBinaryOp::And => "&&",
+      DeclareBinaryOp::Or => "||",
+    }
+  }
+}
+
+/**
+ * An operator applied to one expression.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DeclareUnaryOp {
+  Neg,
+  BitNot,
+  Not,
+  // Takes a pointer to a buffer or variable, `&x`.
+  AddressOf,
+}
+impl DeclareUnaryOp {
+  pub(crate) fn symbol(&self) -> &'static str {
+    match *self {
+      DeclareUnaryOp::Neg => "-",
+      DeclareUnaryOp::BitNot => "~",
+      DeclareUnaryOp::Not => "!",
+      DeclareUnaryOp::AddressOf => "&",
+    }
+  }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
-use super::DeclareCodeExpr;
+use crate::types::{DataTypeInfo, SharedName};
+use super::{DeclareCodeBody, DeclareCodeExpr, DeclareBinaryOp};
 
 
 /**
@@ -10,21 +11,258 @@ use super::DeclareCodeExpr;
 #[derive(Clone)]
 pub(crate) enum DeclareCodeStatement {
   ReturnValue(DeclareCodeExpr),
+  // A call made for its effects, such as `atomicStore(p, v);`.
+  Call(DeclareCodeExpr),
+  Store {
+    buffer: SharedName,
+    index: DeclareCodeExpr,
+    value: DeclareCodeExpr,
+  },
+  Let { name: SharedName, data_type: DataTypeInfo, value: DeclareCodeExpr },
+  Var { name: SharedName, data_type: DataTypeInfo, init: DeclareCodeExpr },
+  // Assigns `value` to a variable, combined with its current value by
+  // `op` if given.
+  Assign {
+    name: SharedName,
+    op: Option<DeclareBinaryOp>,
+    value: DeclareCodeExpr,
+  },
+  If {
+    cond: DeclareCodeExpr,
+    then: DeclareCodeBody,
+    els: Option<DeclareCodeBody>,
+  },
+  Loop { body: DeclareCodeBody },
+  While { cond: DeclareCodeExpr, body: DeclareCodeBody },
+  // Counts `var` up from `lo` while it is less than `hi`.
+  ForRange {
+    var: SharedName,
+    data_type: DataTypeInfo,
+    lo: DeclareCodeExpr,
+    hi: DeclareCodeExpr,
+    body: DeclareCodeBody,
+  },
+  Break,
+  Continue,
 }
 impl DeclareCodeStatement {
   pub(crate) fn new_return(expr: DeclareCodeExpr) -> Self {
     DeclareCodeStatement::ReturnValue(expr)
   }
 
-  pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
+  pub(crate) fn new_call(call: DeclareCodeExpr) -> Self {
+    DeclareCodeStatement::Call(call)
+  }
+
+  pub(crate) fn new_store(
+    buffer: SharedName,
+    index: DeclareCodeExpr,
+    value: DeclareCodeExpr,
+  ) -> Self {
+    DeclareCodeStatement::Store { buffer, index, value }
+  }
+
+  pub(crate) fn new_let(
+    name: SharedName,
+    data_type: DataTypeInfo,
+    value: DeclareCodeExpr,
+  ) -> Self {
+    DeclareCodeStatement::Let { name, data_type, value }
+  }
+
+  pub(crate) fn new_var(
+    name: SharedName,
+    data_type: DataTypeInfo,
+    init: DeclareCodeExpr,
+  ) -> Self {
+    DeclareCodeStatement::Var { name, data_type, init }
+  }
+
+  pub(crate) fn new_assign(
+    name: SharedName,
+    op: Option<DeclareBinaryOp>,
+    value: DeclareCodeExpr,
+  ) -> Self {
+    DeclareCodeStatement::Assign { name, op, value }
+  }
+
+  pub(crate) fn new_if(cond: DeclareCodeExpr, then: DeclareCodeBody)
+    -> Self
+  {
+    DeclareCodeStatement::If { cond, then, els: None }
+  }
+
+  pub(crate) fn new_loop(body: DeclareCodeBody) -> Self {
+    DeclareCodeStatement::Loop { body }
+  }
+
+  pub(crate) fn new_while(cond: DeclareCodeExpr, body: DeclareCodeBody)
+    -> Self
+  {
+    DeclareCodeStatement::While { cond, body }
+  }
+
+  pub(crate) fn new_for_range(
+    var: SharedName,
+    data_type: DataTypeInfo,
+    lo: DeclareCodeExpr,
+    hi: DeclareCodeExpr,
+    body: DeclareCodeBody,
+  ) -> Self {
+    DeclareCodeStatement::ForRange { var, data_type, lo, hi, body }
+  }
+
+  /**
+   * Attach an else body to an if statement.  Returns false if this
+   * is not an if statement, or already has an else body.
+   */
+  pub(crate) fn set_else(&mut self, body: DeclareCodeBody) -> bool {
+    match *self {
+      DeclareCodeStatement::If { ref mut els, .. } if els.is_none() => {
+        *els = Some(body);
+        true
+      },
+      _ => false,
+    }
+  }
+
+  /**
+   * Add the types which must be declared for this statement, and
+   * any code bodies within it, to `out`.
+   */
+  pub(crate) fn collect_data_types<'a>(&'a self,
+    out: &mut Vec<&'a DataTypeInfo>
+  ) {
+    match *self {
+      DeclareCodeStatement::ReturnValue(ref expr) |
+      DeclareCodeStatement::Call(ref expr) =>
+        expr.collect_data_types(out),
+      DeclareCodeStatement::Store { ref index, ref value, .. } => {
+        index.collect_data_types(out);
+        value.collect_data_types(out);
+      },
+      DeclareCodeStatement::Let { ref data_type, ref value, .. } => {
+        out.push(data_type);
+        value.collect_data_types(out);
+      },
+      DeclareCodeStatement::Var { ref data_type, ref init, .. } => {
+        out.push(data_type);
+        init.collect_data_types(out);
+      },
+      DeclareCodeStatement::Assign { ref value, .. } =>
+        value.collect_data_types(out),
+      DeclareCodeStatement::If { ref cond, ref then, ref els } => {
+        cond.collect_data_types(out);
+        then.collect_data_types(out);
+        if let Some(ref els) = *els {
+          els.collect_data_types(out);
+        }
+      },
+      DeclareCodeStatement::Loop { ref body } =>
+        body.collect_data_types(out),
+      DeclareCodeStatement::While { ref cond, ref body } => {
+        cond.collect_data_types(out);
+        body.collect_data_types(out);
+      },
+      DeclareCodeStatement::ForRange { ref lo, ref hi, ref body, .. } => {
+        lo.collect_data_types(out);
+        hi.collect_data_types(out);
+        body.collect_data_types(out);
+      },
+      DeclareCodeStatement::Break |
+      DeclareCodeStatement::Continue => {},
+    }
+  }
+
+  /** Write the statement, indented to `depth` levels. */
+  pub(crate) fn to_text<W>(&self, out: &mut W, depth: usize) -> fmt::Result
     where W: fmt::Write
   {
-    match self {
-      &DeclareCodeStatement::ReturnValue(ref expr) => {
+    write_indent(out, depth) ?;
+    match *self {
+      DeclareCodeStatement::ReturnValue(ref expr) => {
         write!(out, "return ") ?;
         expr.to_text(out) ?;
         writeln!(out, ";")
-      }
+      },
+      DeclareCodeStatement::Call(ref call) => {
+        call.to_text(out) ?;
+        writeln!(out, ";")
+      },
+      DeclareCodeStatement::Store { ref buffer, ref index, ref value } => {
+        write!(out, "{}[", buffer.as_ref()) ?;
+        index.to_text(out) ?;
+        write!(out, "] = ") ?;
+        value.to_text(out) ?;
+        writeln!(out, ";")
+      },
+      DeclareCodeStatement::Let { ref name, ref data_type, ref value } => {
+        write!(out, "let {}: {} = ", name.as_ref(), data_type.wgsl_name()) ?;
+        value.to_text(out) ?;
+        writeln!(out, ";")
+      },
+      DeclareCodeStatement::Var { ref name, ref data_type, ref init } => {
+        write!(out, "var {}: {} = ", name.as_ref(), data_type.wgsl_name()) ?;
+        init.to_text(out) ?;
+        writeln!(out, ";")
+      },
+      DeclareCodeStatement::Assign { ref name, op, ref value } => {
+        match op {
+          Some(op) => write!(out, "{} {}= ", name.as_ref(), op.symbol()) ?,
+          None => write!(out, "{} = ", name.as_ref()) ?,
+        }
+        value.to_text(out) ?;
+        writeln!(out, ";")
+      },
+      DeclareCodeStatement::If { ref cond, ref then, ref els } => {
+        write!(out, "if (") ?;
+        cond.to_text(out) ?;
+        writeln!(out, ") {{") ?;
+        then.to_text(out, depth + 1) ?;
+        if let Some(ref els) = *els {
+          write_indent(out, depth) ?;
+          writeln!(out, "}} else {{") ?;
+          els.to_text(out, depth + 1) ?;
+        }
+        write_indent(out, depth) ?;
+        writeln!(out, "}}")
+      },
+      DeclareCodeStatement::Loop { ref body } => {
+        writeln!(out, "loop {{") ?;
+        body.to_text(out, depth + 1) ?;
+        write_indent(out, depth) ?;
+        writeln!(out, "}}")
+      },
+      DeclareCodeStatement::While { ref cond, ref body } => {
+        write!(out, "while (") ?;
+        cond.to_text(out) ?;
+        writeln!(out, ") {{") ?;
+        body.to_text(out, depth + 1) ?;
+        write_indent(out, depth) ?;
+        writeln!(out, "}}")
+      },
+      DeclareCodeStatement::ForRange {
+        ref var, ref data_type, ref lo, ref hi, ref body
+      } => {
+        let var = var.as_ref();
+        write!(out, "for (var {}: {} = ", var, data_type.wgsl_name()) ?;
+        lo.to_text(out) ?;
+        write!(out, "; {} < ", var) ?;
+        hi.to_text(out) ?;
+        writeln!(out, "; {}++) {{", var) ?;
+        body.to_text(out, depth + 1) ?;
+        write_indent(out, depth) ?;
+        writeln!(out, "}}")
+      },
+      DeclareCodeStatement::Break => writeln!(out, "break;"),
+      DeclareCodeStatement::Continue => writeln!(out, "continue;"),
     }
   }
-}
\ No newline at end of file
+}
+
+fn write_indent<W: fmt::Write>(out: &mut W, depth: usize) -> fmt::Result {
+  for _ in 0 .. depth {
+    write!(out, "  ") ?;
+  }
+  Ok(())
+}
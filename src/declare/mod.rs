@@ -1,5 +1,5 @@
 
-/**
+/*!
  * This module defines all the types, interfaces, and logic used
  * to declare runtime constructs - e.g. shader functions,
  * shader files, buffers, and constructs.
@@ -8,6 +8,12 @@
 /** Declaration of buffers. */
 mod buffer;
 
+/** Declaration of WGSL structs for record types. */
+mod record;
+
+/** Assignment of groups and bindings to shader resources. */
+mod binding;
+
 /** Declaration of shader-related entities. */
 mod shader;
 
@@ -16,6 +22,8 @@ mod code;
 
 pub(crate) use self::{
   buffer::DeclareBuffer,
+  binding::DeclareBinding,
+  record::DeclareRecord,
   shader::{
     DeclareShaderFunction,
     DeclareShaderFile,
@@ -24,5 +32,7 @@ pub(crate) use self::{
     DeclareCodeBody,
     DeclareCodeStatement,
     DeclareCodeExpr,
+    DeclareBinaryOp,
+    DeclareUnaryOp,
   },
 };
\ No newline at end of file
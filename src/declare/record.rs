@@ -0,0 +1,75 @@
+use std::fmt;
+use crate::types::{DataTypeInfo, SharedRecordInfo};
+
+/**
+ * The declaration of a WGSL struct for a record type used by a
+ * shader file.
+ */
+pub(crate) struct DeclareRecord {
+  info: SharedRecordInfo,
+}
+impl DeclareRecord {
+  /**
+   * Collect the records referenced by `data_types`, including the
+   * records nested in their fields.  Each record comes after the
+   * records it depends on.
+   */
+  pub(crate) fn collect<'a, I>(data_types: I) -> Vec<DeclareRecord>
+    where I: IntoIterator<Item = &'a DataTypeInfo>
+  {
+    let mut records = Vec::new();
+    for data_type in data_types {
+      DeclareRecord::collect_into(data_type, &mut records);
+    }
+    records
+  }
+
+  fn collect_into(data_type: &DataTypeInfo, records: &mut Vec<DeclareRecord>) {
+    let info = match *data_type {
+      DataTypeInfo::Record(ref record_type) => record_type.record_info(),
+      DataTypeInfo::Array(ref array) =>
+        return DeclareRecord::collect_into(array.element(), records),
+      _ => return,
+    };
+    if records.iter().any(|r| &r.info == info) {
+      return;
+    }
+    for field in info.fields() {
+      DeclareRecord::collect_into(field.data_type(), records);
+    }
+    records.push(DeclareRecord { info: info.clone() });
+  }
+
+  /**
+   * Write the struct.  WGSL lays out struct members like the
+   * record builder, so a member only needs a `@size` attribute
+   * when the record leaves more space after it than WGSL would.
+   *
+   * No member needs an `@align` attribute: the builder places each
+   * field at the first offset aligned to its WGSL type, so WGSL
+   * rounds the end of the previous member (with its `@size`) up to
+   * the same offset.  Field offsets come from the builder, not
+   * from the memory layout of the rust type.
+   */
+  pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
+    where W: fmt::Write
+  {
+    let fields = self.info.fields();
+    writeln!(out, "struct {} {{", self.info.wgsl_name().as_ref()) ?;
+    for (i, field) in fields.iter().enumerate() {
+      let end = field.offset() + field.data_type().size();
+      let (next_offset, next_align) = match fields.get(i + 1) {
+        Some(next) => (next.offset(), next.data_type().align()),
+        None => (self.info.size(), self.info.align()),
+      };
+      let natural_next = (end + (next_align - 1)) & !(next_align - 1);
+      write!(out, "  ") ?;
+      if next_offset != natural_next {
+        write!(out, "@size({}) ", next_offset - field.offset()) ?;
+      }
+      writeln!(out, "{}: {},",
+        field.name().as_ref(), field.data_type().wgsl_name()) ?;
+    }
+    writeln!(out, "}}")
+  }
+}
@@ -1,8 +1,14 @@
 
 use std::fmt;
 use crate::{
+  PewterError,
   types::SharedName,
-  declare::{DeclareShaderFunction, DeclareBuffer},
+  declare::{
+    DeclareShaderFunction,
+    DeclareBuffer,
+    DeclareBinding,
+    DeclareRecord,
+  },
 };
 
 /**
@@ -30,39 +36,90 @@ impl DeclareShaderFile {
   }
 
   pub(crate) fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn buffers(&self) -> &[DeclareBuffer] { &self.buffers }
+
+  /** The bindings of the buffers, in declaration order. */
+  pub(crate) fn buffer_bindings(&self) -> Vec<DeclareBinding> {
+    (0 .. self.buffers.len()).map(DeclareBinding::for_buffer).collect()
+  }
+
+  /** The bindings of the uniforms pewter provides to this file. */
+  pub(crate) fn uniform_bindings(&self) -> Vec<DeclareBinding> {
+    let mut bindings = Vec::new();
+    if !self.entrypoints.is_empty() {
+      bindings.push(DeclareBinding::grid_size());
+    }
+    if !self.buffers.is_empty() {
+      bindings.push(DeclareBinding::buffer_shapes());
+    }
+    bindings
+  }
+
+  pub(crate) fn entrypoint(&self, name: &str)
+    -> Option<&DeclareShaderFunction>
+  {
+    if !self.entrypoints.iter().any(|e| e.as_ref() == name) {
+      return None;
+    }
+    self.functions.iter().find(|f| f.name().as_ref() == name)
+  }
 
   pub(crate) fn push_function(&mut self,
     function: DeclareShaderFunction,
     is_entrypoint: bool
-  ) -> usize {
-    debug_assert!(
-      self.functions.iter()
-        .find(|f| f.name() == function.name())
-        .is_none(),
-      "Duplicate function name {:?}",
-      function.name()
-    );
+  ) -> Result<usize, PewterError> {
+    if self.functions.iter().any(|f| f.name() == function.name()) {
+      return Err(PewterError::FunctionNameCollision {
+        shader_file: self.name.clone(),
+        function: function.name().clone(),
+      });
+    }
     let idx = self.functions.len();
     if is_entrypoint {
       self.entrypoints.push(function.name().clone());
     }
     self.functions.push(function);
-    idx
+    Ok(idx)
   }
 
   pub(crate) fn push_buffer(&mut self, buffer: DeclareBuffer)
-    -> usize
+    -> Result<usize, PewterError>
   {
-    debug_assert!(
-      self.buffers.iter()
-        .find(|b| b.name() == buffer.name())
-        .is_none(),
-      "Duplicate buffer name {:?}",
-      buffer.name()
-    );
+    if self.buffers.iter().any(|b| b.name() == buffer.name()) {
+      return Err(PewterError::BufferNameCollision {
+        shader_file: self.name.clone(),
+        buffer: buffer.name().clone(),
+      });
+    }
     let idx = self.buffers.len();
     self.buffers.push(buffer);
-    idx
+    Ok(idx)
+  }
+
+  /**
+   * Check that the text of this file is valid WGSL.
+   */
+  pub(crate) fn validate(&self) -> Result<(), PewterError> {
+    let mut text = String::with_capacity(1024);
+    self.to_text(&mut text)
+      .expect("Failed to write shader file text");
+    let module = naga::front::wgsl::parse_str(&text).map_err(|err| {
+      PewterError::ShaderParse {
+        shader_file: self.name.clone(),
+        message: err.emit_to_string(&text),
+      }
+    }) ?;
+    let mut validator = naga::valid::Validator::new(
+      naga::valid::ValidationFlags::all(),
+      naga::valid::Capabilities::empty(),
+    );
+    validator.validate(&module).map_err(|err| {
+      PewterError::ShaderValidation {
+        shader_file: self.name.clone(),
+        message: err.emit_to_string(&text),
+      }
+    }) ?;
+    Ok(())
   }
 
   pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
@@ -73,24 +130,58 @@ impl DeclareShaderFile {
     writeln!(out, "/// FILE: {}", self.name.as_ref()) ?;
     writeln!(out, "//////") ?;
     writeln!(out, "//////") ?;
-    // Write out each function.
-    writeln!(out, "") ?;
-    writeln!(out, "//") ?;
-    writeln!(out, "// FUNCTIONS") ?;
-    writeln!(out, "//") ?;
-    for func in &self.functions {
-      writeln!(out, "// Function {}", func.name().as_ref()) ?;
-      func.to_text(out) ?
+    // Write out the structs for the records used by the file.
+    let data_types = self.buffers.iter().flat_map(|b| b.data_types())
+      .chain(self.functions.iter().flat_map(|f| f.data_types()));
+    let records = DeclareRecord::collect(data_types);
+    if !records.is_empty() {
+      writeln!(out) ?;
+      writeln!(out, "//") ?;
+      writeln!(out, "// STRUCTS") ?;
+      writeln!(out, "//") ?;
+      for record in records.iter() {
+        record.to_text(out) ?;
+      }
     }
 
     // Write out each buffer.
-    writeln!(out, "") ?;
+    writeln!(out) ?;
     writeln!(out, "//") ?;
     writeln!(out, "// BUFFERS") ?;
     writeln!(out, "//") ?;
-    for buf in &self.buffers {
+    for (buf, binding) in self.buffers.iter().zip(self.buffer_bindings()) {
       writeln!(out, "// buf {}", buf.name().as_ref()) ?;
-      // buf.to_text(out) ?
+      buf.to_text(out, binding) ?
+    }
+
+    // The extents of each bound buffer, used to index
+    // multi-dimensional buffers.
+    if !self.buffers.is_empty() {
+      writeln!(out) ?;
+      DeclareBinding::buffer_shapes().to_text(out) ?;
+      writeln!(out, "var<uniform> x_buffer_shapes: array<vec4<u32>, {}>;",
+        self.buffers.len()) ?;
+      for (i, buf) in self.buffers.iter().enumerate() {
+        buf.index_helper_to_text(out, i) ?;
+      }
+    }
+
+    // The extent of the dispatched grid, used by entrypoints to
+    // skip invocations past its edge.
+    if !self.entrypoints.is_empty() {
+      writeln!(out) ?;
+      DeclareBinding::grid_size().to_text(out) ?;
+      writeln!(out, "var<uniform> x_grid_size: vec3<u32>;") ?;
+    }
+
+    // Write out each function.
+    writeln!(out) ?;
+    writeln!(out, "//") ?;
+    writeln!(out, "// FUNCTIONS") ?;
+    writeln!(out, "//") ?;
+    for func in &self.functions {
+      writeln!(out, "// Function {}", func.name().as_ref()) ?;
+      func.to_text(out) ?
     }
     Ok(())
   }
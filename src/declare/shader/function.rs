@@ -1,6 +1,8 @@
 use std::fmt;
 use crate::{
-  types::{DataTypeInfo, SharedName, data_type_info_for},
+  PewterError,
+  shader::ShaderFunction,
+  types::{DataTypeInfo, SharedName, data_type_info_for, intern_name},
   declare::DeclareCodeBody
 };
 
@@ -11,12 +13,12 @@ pub(crate) struct DeclareShaderFunction {
   // The name of the function.
   name: SharedName,
 
-  // The buffer binding info for the function.
-  buffers: Vec<DataTypeInfo>,
-
   // The arguments info for the function.
   arguments: Vec<(SharedName, DataTypeInfo)>,
 
+  // The names of the local variables declared in the body.
+  locals: Vec<SharedName>,
+
   // If this function is an entry-point.
   is_entry: bool,
 
@@ -32,26 +34,54 @@ impl DeclareShaderFunction {
     is_entry: bool,
     ret: Option<DataTypeInfo>
   ) -> Self {
-    let buffers = Vec::new();
     let arguments = Vec::new();
+    let locals = Vec::new();
     let body = DeclareCodeBody::new();
     DeclareShaderFunction {
-      name, buffers, arguments, is_entry, ret, body
+      name, arguments, locals, is_entry, ret, body
     }
   }
 
   pub(crate) fn name(&self) -> &SharedName { &self.name }
 
-  pub(crate) fn get_bound_buffer(&self, idx: usize) -> &DataTypeInfo {
-    &self.buffers[idx]
+  /**
+   * The number of grid dimensions an entry function is dispatched
+   * over, determined by the type of its coordinate argument.
+   */
+  pub(crate) fn entry_dims(&self) -> Option<usize> {
+    if !self.is_entry {
+      return None;
+    }
+    let dt = &self.arguments[0].1;
+    if dt == &data_type_info_for::<u32>() {
+      Some(1)
+    } else if dt == &data_type_info_for::<[u32; 2]>() {
+      Some(2)
+    } else {
+      None
+    }
   }
 
-  pub(crate) fn push_bound_buffer(&mut self, data_type: DataTypeInfo)
-    -> usize
-  {
-    let idx = self.buffers.len();
-    self.buffers.push(data_type);
-    idx
+  /** The workgroup size an entry function is compiled with. */
+  pub(crate) fn workgroup_size(&self) -> [u32; 3] {
+    match self.entry_dims() {
+      Some(2) => [8, 8, 1],
+      _ => [64, 1, 1],
+    }
+  }
+
+  /**
+   * The argument and result types callers must match, for functions
+   * which are not entry-points.
+   */
+  pub(crate) fn signature(&self) -> Option<ShaderFunction> {
+    if self.is_entry {
+      return None;
+    }
+    let arguments = self.arguments.iter()
+      .map(|(_, dt)| dt.clone())
+      .collect::<Vec<_>>();
+    self.ret.clone().map(|ret| ShaderFunction::new(arguments, ret))
   }
 
   pub(crate) fn argument_type(&self, idx: usize) -> &DataTypeInfo {
@@ -64,47 +94,95 @@ impl DeclareShaderFunction {
   pub(crate) fn push_argument(&mut self,
     name: &SharedName,
     data_type: DataTypeInfo
-  ) {
-    assert!(self.arguments.iter().find(|(n, _)| n == name).is_none(),
-      "Argument name collision: {:?}", name);
+  ) -> Result<(), PewterError> {
+    if self.arguments.iter().any(|(n, _)| n == name) {
+      return Err(PewterError::ArgumentNameCollision {
+        function: self.name.clone(),
+        argument: name.clone(),
+      });
+    }
     self.arguments.push((name.clone(), data_type));
+    Ok(())
+  }
+
+  /**
+   * Reserve a name for a local variable, based on `name`.  If an
+   * argument or another local already uses `name`, a numeric suffix
+   * is added to make it unique.
+   */
+  pub(crate) fn add_local(&mut self, name: &str) -> SharedName {
+    let is_used = |this: &Self, nm: &str| {
+      this.arguments.iter().any(|(n, _)| n.as_ref() == nm)
+        || this.locals.iter().any(|n| n.as_ref() == nm)
+    };
+    let mut local = name.to_owned();
+    let mut suffix = 1;
+    while is_used(self, &local) {
+      local = format!("{}_{}", name, suffix);
+      suffix += 1;
+    }
+    let local = intern_name(&local);
+    self.locals.push(local.clone());
+    local
   }
 
   pub(crate) fn body(&mut self) -> &mut DeclareCodeBody {
     &mut self.body
   }
 
+  /**
+   * The types of the arguments and the return value, and those
+   * used within the body.
+   */
+  pub(crate) fn data_types(&self) -> Vec<&DataTypeInfo> {
+    let mut data_types = self.arguments.iter()
+      .map(|(_, dt)| dt)
+      .chain(self.ret.iter())
+      .collect::<Vec<_>>();
+    self.body.collect_data_types(&mut data_types);
+    data_types
+  }
+
   pub(crate) fn to_text<W>(&self, out: &mut W) -> fmt::Result
     where W: fmt::Write
   {
+    if self.is_entry {
+      let [x, y, z] = self.workgroup_size();
+      writeln!(out, "@compute @workgroup_size({}, {}, {})", x, y, z) ?;
+    }
     write!(out, "fn {}(", self.name.as_ref()) ?;
-    for (i, &(ref nm, ref dt)) in self.arguments.iter().enumerate() {
+    for (i, (nm, dt)) in self.arguments.iter().enumerate() {
       if i > 0 { write!(out, ", ") ?; }
 
       if self.is_entry {
         debug_assert!(i == 0, "Entry function should take 1 arg.");
         write!(out, "\n  @builtin(global_invocation_id) x_global_id: vec3<u32>") ?;
       } else {
-        write!(out, "{}: {}", nm.as_ref(), dt.name()) ?;
+        write!(out, "{}: {}", nm.as_ref(), dt.wgsl_name()) ?;
       }
     }
-    writeln!(out, ") {{") ?;
+    write!(out, ")") ?;
+    if let Some(ref ret) = self.ret {
+      write!(out, " -> {}", ret.wgsl_name()) ?;
+    }
+    writeln!(out, " {{") ?;
     if self.is_entry {
+      // Invocations past the edge of the dispatched grid do nothing.
+      writeln!(out, "  if (any(x_global_id >= x_grid_size)) {{ return; }}") ?;
       let dt = &self.arguments[0].1;
       if dt == &data_type_info_for::<u32>() {
-        writeln!(out, "let {}: u32 = x_global_id.x;",
+        writeln!(out, "  let {}: u32 = x_global_id.x;",
           self.arguments[0].0.as_ref()) ?;
       } else if dt == &data_type_info_for::<[u32; 2]>() {
-        writeln!(out, "let {}: vec2<u32> = x_global_id.xy;",
+        writeln!(out, "  let {}: vec2<u32> = x_global_id.xy;",
           self.arguments[0].0.as_ref()) ?;
       } else {
-        panic!("Unknown entry-function type {}", dt.name());
+        // Entry functions are checked by `entry_dims` when they
+        // are declared.
+        return Err(fmt::Error);
       }
     }
-    for stmt in self.body.statements() {
-      write!(out, "  ") ?;
-      stmt.to_text(out) ?;
-    }
+    self.body.to_text(out, 1) ?;
     writeln!(out, "}}") ?;
     Ok(())
   }
@@ -5,6 +5,7 @@ mod buf;
 mod shader;
 
 mod declare;
+mod construct;
 
 mod session;
 mod pewter;
@@ -12,7 +13,90 @@ mod pewter;
 #[cfg(test)]
 mod test;
 
+pub use pewter_derive::{ DataType, RecordType };
+
 pub use self::{
-  pewter::{ Pewter, PewterConfig, PewterError },
-  session::{ ConstructSession },
-};
\ No newline at end of file
+  pewter::{ Pewter, PewterConfig, PewterConstruct, PewterError },
+  session::{
+    ConstructSession,
+    ConstructDispatch,
+    SessionBuffer,
+    SessionTailBuffer,
+    SessionCodeExpr,
+    SessionCodeExprTuple,
+    SessionFunction,
+    lit,
+    SessionIf,
+    SessionScope,
+    SessionVar,
+    ArithmeticWith,
+    AddWith,
+    MultiplyWith,
+    Negatable,
+    Bitwise,
+    ShiftableBy,
+    Comparable,
+    Ordered,
+    Logical,
+    LoopIndex,
+    Float,
+    FloatVector,
+    NumericVector,
+    SquareMatrix,
+    select,
+    ConvertibleTo,
+    BitcastTo,
+    Component,
+    ComponentOf,
+    Swizzle,
+    X, Y, Z, W,
+    vec2, vec3, vec4,
+  },
+  buf::{
+    TypedBuffer,
+    TailBuffer,
+    BufferShape,
+    BufferCoord,
+    BufferAccess,
+    AccessMode,
+    StorageAccess,
+    WritableAccess,
+    ReadAccess,
+    ReadWriteAccess,
+    UniformAccess,
+  },
+  construct::{
+    ConstructOutputs,
+    ConstructReadback,
+    ConstructTailReadback,
+  },
+  types::{
+    SharedName,
+    DataType,
+    DataTypeInfo,
+    DataTypeSpecialize,
+    DataTypeSpecializeRef,
+    DataTypeSpecializeMut,
+    DataTypeTuple,
+    PrimType,
+    PrimTypeInfo,
+    ScalarType,
+    ScalarTypeInfo,
+    VectorType,
+    VectorTypeInfo,
+    VectorOf,
+    MatrixType,
+    MatrixTypeInfo,
+    RecordType,
+    RecordTypeFieldSpecifier,
+    ArrayType,
+    ArrayTypeInfo,
+    FixedArray,
+    Atomic,
+    AtomicScalar,
+    AtomicTypeInfo,
+    NonAtomic,
+    RecordInfo,
+    TypedRecordInfo,
+  },
+};
@@ -1,34 +1,62 @@
 use std::{
-  sync::Mutex,
+  fmt,
+  sync::{Arc, Mutex},
   collections::HashMap,
-  marker::PhantomData,
+  borrow::Cow,
 };
 use crate::{
-  types::SharedName,
-  buf::Buffer,
+  types::{SharedName, DataType, data_type_info_for, intern_name},
+  buf::{Buffer, BufferAccess, BufferShape, TypedBuffer, read_mappable_buffer},
   session::{ConstructSession, DeclareSession},
-  declare::DeclareShaderFile,
+  declare::{DeclareShaderFile, DeclareBinding},
+  construct::{
+    ConstructStep, DispatchStep, DispatchSlot, ConstructOutputs,
+    ConstructResultFn,
+  },
 };
 
 
 pub struct Pewter {
   config: PewterConfig,
 
+  // The device and queue used to run constructs.
+  device: Arc<wgpu::Device>,
+  queue: Arc<wgpu::Queue>,
+
   // The persistent buffers loaded by this instance.
   buffers: Mutex<HashMap<SharedName, Buffer>>,
 
-  // The declared shaders.
-  shaders: Mutex<HashMap<SharedName, DeclareShaderFile>>,
+  // The declared shaders, with their compiled modules.
+  shaders: Mutex<HashMap<SharedName, Arc<CompiledShaderFile>>>,
 }
 impl Pewter {
   pub async fn new(config: PewterConfig)
     -> Result<Pewter, PewterError>
   {
+    let (device, queue) = match config.shared_device {
+      Some((ref device, ref queue)) => {
+        // A device shared by the host application must already
+        // provide everything the configuration requires.
+        check_features(&config.features, &device.features()) ?;
+        check_limits(&config.limits, &device.limits()) ?;
+        (device.clone(), queue.clone())
+      },
+      None => {
+        let (device, queue) = request_device(&config).await ?;
+        (Arc::new(device), Arc::new(queue))
+      },
+    };
+
     let buffers = Mutex::new(HashMap::new());
     let shaders = Mutex::new(HashMap::new());
-    Ok(Pewter { config, buffers, shaders })
+    Ok(Pewter { config, device, queue, buffers, shaders })
   }
 
+  pub fn config(&self) -> &PewterConfig { &self.config }
+
+  pub fn device(&self) -> &Arc<wgpu::Device> { &self.device }
+  pub fn queue(&self) -> &Arc<wgpu::Queue> { &self.queue }
+
   pub fn declare<F>(&self, func: F) -> Result<(), PewterError>
     where F: FnOnce (&mut DeclareSession) -> Result<(), PewterError>
   {
@@ -38,54 +66,699 @@ impl Pewter {
   }
 
   pub fn construct<R: 'static, F>(&self, func: F)
-    -> Result<PewterConstruct<R>, PewterError>
-    where F: FnOnce (&mut ConstructSession<R>)
+    -> Result<PewterConstruct<'_, R>, PewterError>
+    where F: FnOnce (&mut ConstructSession<R>) -> Result<(), PewterError>
   {
     let mut session = ConstructSession::new(self);
-    func(&mut session);
+    func(&mut session) ?;
     let construct = session.make_construct() ?;
     Ok(construct)
   }
 
+  /**
+   * Create a persistent buffer of `len` elements, which lives
+   * as long as this instance.
+   */
+  pub fn create_buffer<DT>(&self, name: &str, len: usize)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let extent = u32::try_from(len).map_err(|_| {
+      PewterError::BufferTooLarge { buffer: intern_name(name), len }
+    }) ?;
+    self.create_shaped_buffer(name, BufferShape::new_1d(extent))
+  }
+
+  /**
+   * Create a persistent buffer with a 1d, 2d or 3d shape, which
+   * lives as long as this instance.
+   */
+  pub fn create_shaped_buffer<DT>(&self, name: &str, shape: BufferShape)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let name = intern_name(name);
+    let mut locked = self.buffers.lock()
+      .expect("Failed to lock buffers registry");
+    if locked.contains_key(&name) {
+      return Err(PewterError::PersistentBufferNameCollision(name));
+    }
+    let data_type = data_type_info_for::<DT>();
+    let buffer = Buffer::new(
+      &self.device,
+      &self.queue,
+      name.clone(),
+      data_type,
+      shape,
+    ) ?;
+    locked.insert(name, buffer.clone());
+    Ok(TypedBuffer::new(buffer))
+  }
+
+  /**
+   * Create a persistent uniform buffer holding a single value,
+   * which lives as long as this instance.
+   */
+  pub fn create_uniform_buffer<DT>(&self, name: &str)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let name = intern_name(name);
+    let mut locked = self.buffers.lock()
+      .expect("Failed to lock buffers registry");
+    if locked.contains_key(&name) {
+      return Err(PewterError::PersistentBufferNameCollision(name));
+    }
+    let data_type = data_type_info_for::<DT>();
+    let buffer = Buffer::new_uniform(
+      &self.device,
+      &self.queue,
+      name.clone(),
+      data_type,
+    ) ?;
+    locked.insert(name, buffer.clone());
+    Ok(TypedBuffer::new(buffer))
+  }
+
+  /** Look up a persistent buffer by name. */
+  pub fn buffer<DT>(&self, name: &str)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let locked = self.buffers.lock()
+      .expect("Failed to lock buffers registry");
+    let buffer = locked.get(name).ok_or_else(|| {
+      PewterError::UnknownPersistentBuffer(intern_name(name))
+    }) ?;
+    let data_type = data_type_info_for::<DT>();
+    if buffer.data_type() != &data_type {
+      return Err(PewterError::BufferTypeMismatch {
+        slot: buffer.name().clone(),
+        expected: buffer.data_type().name().to_owned(),
+        actual: data_type.name().to_owned(),
+      });
+    }
+    Ok(TypedBuffer::new(buffer.clone()))
+  }
+
   pub(crate) fn register_shader_file(&self,
     shader_file: DeclareShaderFile
-  ) {
+  ) -> Result<(), PewterError> {
+    shader_file.validate() ?;
+    let name = shader_file.name().clone();
+    if self.lookup_shader_file(&name).is_some() {
+      return Err(PewterError::ShaderFileNameCollision(name));
+    }
+    // The module is compiled without holding the lock, so the name
+    // is checked again in case it was registered meanwhile.
+    let compiled = CompiledShaderFile::new(&self.device, shader_file);
     let mut locked = self.shaders.lock()
       .expect("Failed to lock shaders registry");
-    assert!(locked.get(shader_file.name()).is_none(),
-      "Shader file name collision {:?}", shader_file.name());
-    locked.insert(shader_file.name().clone(), shader_file);
+    if locked.contains_key(&name) {
+      return Err(PewterError::ShaderFileNameCollision(name));
+    }
+    locked.insert(name, Arc::new(compiled));
+    Ok(())
   }
 
-  pub(crate) fn shader_file_text(&self, name: &SharedName)
-    -> Option<String>
+  fn lookup_shader_file(&self, name: &SharedName)
+    -> Option<Arc<CompiledShaderFile>>
   {
     let locked = self.shaders.lock()
       .expect("Failed to lock shaders registry");
-    let decl_file = locked.get(name) ?;
+    locked.get(name).cloned()
+  }
+
+  pub(crate) fn shader_file_text(&self, name: &SharedName)
+    -> Option<String>
+  {
+    let compiled = self.lookup_shader_file(name) ?;
     let mut output = String::with_capacity(1024);
-    decl_file.to_text(&mut output);
+    compiled.decl_file.to_text(&mut output)
+      .expect("Failed to write shader file text");
     Some(output)
   }
+
+  /**
+   * Get the pipeline for an entrypoint of a declared shader file,
+   * ready to be dispatched over `grid`.  The pipeline is compiled
+   * the first time the entrypoint is dispatched.
+   */
+  pub(crate) fn prepare_dispatch(&self,
+    shader_file: &SharedName,
+    entry: &str,
+    grid: &[u32],
+  ) -> Result<DispatchStep, PewterError> {
+    let compiled = self.lookup_shader_file(shader_file).ok_or_else(|| {
+      PewterError::UnknownShaderFile(shader_file.clone())
+    }) ?;
+    let decl_file = &compiled.decl_file;
+    let decl_entry = decl_file.entrypoint(entry).ok_or_else(|| {
+      PewterError::UnknownEntryPoint {
+        shader_file: shader_file.clone(),
+        entry: entry.to_owned(),
+      }
+    }) ?;
+    let dims = decl_entry.entry_dims().unwrap_or(0);
+    if grid.len() != dims {
+      return Err(PewterError::GridDimensionMismatch {
+        entry: decl_entry.name().clone(),
+        expected: dims,
+        actual: grid.len(),
+      });
+    }
+    let mut grid_3d = [1_u32; 3];
+    grid_3d[.. dims].copy_from_slice(grid);
+    let workgroup_size = decl_entry.workgroup_size();
+    let max_workgroups =
+      self.device.limits().max_compute_workgroups_per_dimension;
+    let too_large = (0 .. 3).any(|i| {
+      grid_3d[i].div_ceil(workgroup_size[i]) > max_workgroups
+    });
+    if too_large {
+      return Err(PewterError::GridTooLarge {
+        entry: decl_entry.name().clone(),
+        grid: grid.to_vec(),
+        max_workgroups,
+      });
+    }
+
+    let pipeline = compiled.pipeline(&self.device, decl_entry.name());
+    let slots = decl_file.buffers().iter()
+      .map(|b| {
+        DispatchSlot::new(
+          b.name().clone(),
+          b.data_type().clone(),
+          b.access(),
+          b.dims(),
+          b.header().cloned(),
+        )
+      })
+      .collect();
+    Ok(DispatchStep::new(
+      &self.device,
+      decl_entry.name().clone(),
+      pipeline,
+      slots,
+      grid_3d,
+      workgroup_size,
+    ))
+  }
+}
+
+/**
+ * A registered shader file with its compiled module, and the
+ * pipelines of the entrypoints dispatched so far.
+ */
+struct CompiledShaderFile {
+  decl_file: DeclareShaderFile,
+  module: wgpu::ShaderModule,
+  layout: wgpu::PipelineLayout,
+
+  // The pipelines by entrypoint name.
+  pipelines: Mutex<HashMap<SharedName, Arc<wgpu::ComputePipeline>>>,
+}
+impl CompiledShaderFile {
+  fn new(device: &wgpu::Device, decl_file: DeclareShaderFile) -> Self {
+    let label = decl_file.name().clone();
+    let mut text = String::with_capacity(1024);
+    decl_file.to_text(&mut text)
+      .expect("Failed to write shader file text");
+    let module = device.create_shader_module(
+      wgpu::ShaderModuleDescriptor {
+        label: Some(label.as_ref()),
+        source: wgpu::ShaderSource::Wgsl(Cow::Owned(text)),
+      }
+    );
+
+    let mut group_entries = (0 .. DeclareBinding::NUM_GROUPS)
+      .map(|_| Vec::new())
+      .collect::<Vec<_>>();
+    let buffer_bindings = decl_file.buffers().iter()
+      .zip(decl_file.buffer_bindings());
+    for (buf, binding) in buffer_bindings {
+      let ty = match buf.access() {
+        BufferAccess::Read =>
+          wgpu::BufferBindingType::Storage { read_only: true },
+        BufferAccess::ReadWrite =>
+          wgpu::BufferBindingType::Storage { read_only: false },
+        BufferAccess::Uniform =>
+          wgpu::BufferBindingType::Uniform,
+      };
+      group_entries[binding.group() as usize].push(layout_entry(binding, ty));
+    }
+    for binding in decl_file.uniform_bindings() {
+      group_entries[binding.group() as usize].push(layout_entry(binding,
+        wgpu::BufferBindingType::Uniform));
+    }
+    let group_layouts = group_entries.iter()
+      .map(|entries| device.create_bind_group_layout(
+        &wgpu::BindGroupLayoutDescriptor {
+          label: Some(label.as_ref()),
+          entries,
+        }
+      ))
+      .collect::<Vec<_>>();
+    let layout = device.create_pipeline_layout(
+      &wgpu::PipelineLayoutDescriptor {
+        label: Some(label.as_ref()),
+        bind_group_layouts: &group_layouts.iter().collect::<Vec<_>>(),
+        push_constant_ranges: &[],
+      }
+    );
+    let pipelines = Mutex::new(HashMap::new());
+    CompiledShaderFile { decl_file, module, layout, pipelines }
+  }
+
+  /**
+   * The pipeline of the entrypoint `entry`, compiled without holding
+   * the lock on first use.  If two threads compile it at once, the
+   * first one stored is kept.
+   */
+  fn pipeline(&self, device: &wgpu::Device, entry: &SharedName)
+    -> Arc<wgpu::ComputePipeline>
+  {
+    let cached = self.pipelines.lock()
+      .expect("Failed to lock pipelines")
+      .get(entry)
+      .cloned();
+    if let Some(pipeline) = cached {
+      return pipeline;
+    }
+    let pipeline = device.create_compute_pipeline(
+      &wgpu::ComputePipelineDescriptor {
+        label: Some(entry.as_ref()),
+        layout: Some(&self.layout),
+        module: &self.module,
+        entry_point: entry.as_ref(),
+      }
+    );
+    let mut locked = self.pipelines.lock()
+      .expect("Failed to lock pipelines");
+    locked.entry(entry.clone()).or_insert_with(|| Arc::new(pipeline)).clone()
+  }
+}
+
+/** The layout of a buffer bound at `binding`. */
+fn layout_entry(binding: DeclareBinding, ty: wgpu::BufferBindingType)
+  -> wgpu::BindGroupLayoutEntry
+{
+  wgpu::BindGroupLayoutEntry {
+    binding: binding.binding(),
+    visibility: wgpu::ShaderStages::COMPUTE,
+    ty: wgpu::BindingType::Buffer {
+      ty,
+      has_dynamic_offset: false,
+      min_binding_size: None,
+    },
+    count: None,
+  }
+}
+
+/* Global cache of `wgpu::Instance` by the backends it searches. */
+lazy_static::lazy_static! {
+  static ref INSTANCES
+    : Mutex<HashMap<wgpu::Backends, Arc<wgpu::Instance>>>
+    = Mutex::new(HashMap::new());
+}
+
+/**
+ * Get the instance for a set of backends.  Instances are never
+ * dropped: tearing down an instance on the GL backend tears down
+ * the display shared by every other instance in the process.
+ */
+fn instance_for_backends(backends: wgpu::Backends) -> Arc<wgpu::Instance> {
+  let mut locked = INSTANCES.lock()
+    .expect("Failed to lock INSTANCES");
+  locked.entry(backends)
+    .or_insert_with(|| Arc::new(wgpu::Instance::new(backends)))
+    .clone()
+}
+
+async fn request_device(config: &PewterConfig)
+  -> Result<(wgpu::Device, wgpu::Queue), PewterError>
+{
+  let instance = instance_for_backends(config.backends);
+  let adapter = instance.request_adapter(
+    &wgpu::RequestAdapterOptions {
+      power_preference: config.power_preference,
+      force_fallback_adapter: config.force_fallback_adapter,
+      compatible_surface: None,
+    }
+  ).await.ok_or(PewterError::NoAdapter) ?;
+  check_features(&config.features, &adapter.features()) ?;
+  check_limits(&config.limits, &adapter.limits()) ?;
+  adapter.request_device(
+    &wgpu::DeviceDescriptor {
+      label: Some("pewter"),
+      features: config.features,
+      limits: config.limits.clone(),
+    },
+    None
+  ).await.map_err(PewterError::RequestDevice)
+}
+
+fn check_features(required: &wgpu::Features, allowed: &wgpu::Features)
+  -> Result<(), PewterError>
+{
+  let missing = *required - *allowed;
+  if !missing.is_empty() {
+    return Err(PewterError::UnsupportedFeatures(missing));
+  }
+  Ok(())
 }
 
+fn check_limits(required: &wgpu::Limits, allowed: &wgpu::Limits)
+  -> Result<(), PewterError>
+{
+  let mut failure = None;
+  required.check_limits_with_fail_fn(allowed, true,
+    |name, requested, allowed| {
+      failure = Some(PewterError::UnsupportedLimit {
+        name, requested, allowed
+      });
+    }
+  );
+  failure.map_or(Ok(()), Err)
+}
+
+/**
+ * A prepared sequence of operations, which can be performed
+ * any number of times.
+ */
 pub struct PewterConstruct<'a, T> {
-  _dummy: PhantomData<&'a T>,
+  pewter: &'a Pewter,
+  steps: Vec<ConstructStep>,
+  result: ConstructResultFn<T>,
 }
 impl<'a, T> PewterConstruct<'a, T> {
-  pub(crate) async fn perform(&self) -> Result<T, PewterError> {
-    panic!("Implement!");
+  pub(crate) fn new(
+    pewter: &'a Pewter,
+    steps: Vec<ConstructStep>,
+    result: ConstructResultFn<T>,
+  ) -> Self {
+    PewterConstruct { pewter, steps, result }
+  }
+
+  pub async fn perform(&self) -> Result<T, PewterError> {
+    let device = self.pewter.device();
+    let mut encoder = device.create_command_encoder(
+      &wgpu::CommandEncoderDescriptor { label: Some("pewter_construct") }
+    );
+    let mut staging = Vec::new();
+    for step in &self.steps {
+      step.encode(device, &mut encoder, &mut staging);
+    }
+    self.pewter.queue().submit(Some(encoder.finish()));
+
+    let mut readbacks = Vec::with_capacity(staging.len());
+    for buffer in &staging {
+      readbacks.push(read_mappable_buffer(device, buffer).await ?);
+    }
+    let outputs = ConstructOutputs::new(readbacks);
+    Ok((self.result)(&outputs))
   }
 }
 
+/**
+ * Configuration for the device used by a `Pewter` instance.
+ */
 pub struct PewterConfig {
+  // The backends to search for an adapter.
+  backends: wgpu::Backends,
+
+  // The preferred adapter power profile.
+  power_preference: wgpu::PowerPreference,
+
+  // Whether to only accept a fallback (software) adapter.
+  force_fallback_adapter: bool,
+
+  // The features and limits the device must support.
+  features: wgpu::Features,
+  limits: wgpu::Limits,
+
+  // A device and queue shared with the host application.
+  shared_device: Option<(Arc<wgpu::Device>, Arc<wgpu::Queue>)>,
+}
+impl PewterConfig {
+  pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+    self.backends = backends;
+    self
+  }
+
+  pub fn power_preference(mut self, pref: wgpu::PowerPreference) -> Self {
+    self.power_preference = pref;
+    self
+  }
+
+  pub fn force_fallback_adapter(mut self, force: bool) -> Self {
+    self.force_fallback_adapter = force;
+    self
+  }
+
+  pub fn features(mut self, features: wgpu::Features) -> Self {
+    self.features = features;
+    self
+  }
+
+  pub fn limits(mut self, limits: wgpu::Limits) -> Self {
+    self.limits = limits;
+    self
+  }
+
+  /**
+   * Use an existing device and queue instead of requesting a new
+   * one.  The adapter options are ignored in this case.
+   */
+  pub fn shared_device(mut self,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+  ) -> Self {
+    self.shared_device = Some((device, queue));
+    self
+  }
+
+  pub fn get_features(&self) -> wgpu::Features { self.features }
+  pub fn get_limits(&self) -> &wgpu::Limits { &self.limits }
 }
 impl Default for PewterConfig {
   fn default() -> PewterConfig {
-    PewterConfig {  }
+    PewterConfig {
+      backends: wgpu::Backends::all(),
+      power_preference: wgpu::PowerPreference::default(),
+      force_fallback_adapter: false,
+      features: wgpu::Features::empty(),
+      limits: wgpu::Limits::downlevel_defaults(),
+      shared_device: None,
+    }
   }
 }
 
+/**
+ * Errors reported by pewter operations.
+ */
 #[derive(Debug)]
 pub enum PewterError {
-}
\ No newline at end of file
+  // No adapter satisfying the configuration was found.
+  NoAdapter,
+  // The adapter or shared device lacks required features.
+  UnsupportedFeatures(wgpu::Features),
+  // The adapter or shared device does not meet a required limit.
+  UnsupportedLimit { name: &'static str, requested: u64, allowed: u64 },
+  // The adapter failed to provide a device.
+  RequestDevice(wgpu::RequestDeviceError),
+  // Mapping a buffer for reading failed.
+  BufferMap(wgpu::BufferAsyncError),
+
+  // A shader file with the same name was already declared.
+  ShaderFileNameCollision(SharedName),
+  // A shader file declares two functions with the same name.
+  FunctionNameCollision { shader_file: SharedName, function: SharedName },
+  // A shader file declares two buffers with the same name.
+  BufferNameCollision { shader_file: SharedName, buffer: SharedName },
+  // A function declares two arguments with the same name.
+  ArgumentNameCollision { function: SharedName, argument: SharedName },
+  // An entry function's coordinate is not a supported type.
+  UnsupportedEntryCoordinate { function: SharedName, data_type: String },
+  // The text generated for a shader file failed to parse.
+  ShaderParse { shader_file: SharedName, message: String },
+  // The text generated for a shader file failed validation.
+  ShaderValidation { shader_file: SharedName, message: String },
+  // A uniform buffer's type breaks the uniform layout rules at a field.
+  UniformLayout { buffer: SharedName, data_type: String, field: SharedName },
+  // A function was called with arguments of the wrong types.
+  CallArgumentMismatch {
+    function: SharedName,
+    expected: String,
+    actual: String,
+  },
+  // A `break` statement was added outside of a loop body.
+  BreakOutsideLoop { function: SharedName },
+  // A `continue` statement was added outside of a loop body.
+  ContinueOutsideLoop { function: SharedName },
+  // A buffer's type cannot be shared with the host, e.g. bool.
+  NonHostShareableBuffer { buffer: SharedName, data_type: String },
+  // A buffer holding atomics was not a read-write storage buffer.
+  AtomicBufferAccess { buffer: SharedName, data_type: String },
+  // A buffer was indexed by a coordinate of the wrong dimensions.
+  BufferDimensionMismatch {
+    buffer: SharedName,
+    expected: usize,
+    actual: usize,
+  },
+
+  // A persistent buffer with the same name was already created.
+  PersistentBufferNameCollision(SharedName),
+  // No persistent buffer with the given name exists.
+  UnknownPersistentBuffer(SharedName),
+
+  // A construct referenced an undeclared shader file.
+  UnknownShaderFile(SharedName),
+  // A construct referenced an entrypoint its shader file lacks.
+  UnknownEntryPoint { shader_file: SharedName, entry: String },
+  // A dispatch grid does not match its entrypoint's dimensions.
+  GridDimensionMismatch { entry: SharedName, expected: usize, actual: usize },
+  // A dispatch grid needs more workgroups per dimension than the
+  // device allows.
+  GridTooLarge { entry: SharedName, grid: Vec<u32>, max_workgroups: u32 },
+  // A buffer was bound to a slot its shader file lacks.
+  UnknownBuffer { entry: SharedName, slot: SharedName },
+  // A dispatch was left with a buffer slot unbound.
+  UnboundBuffer { entry: SharedName, slot: SharedName },
+  // A buffer was bound to a slot of a different data type.
+  BufferTypeMismatch { slot: SharedName, expected: String, actual: String },
+  // A storage buffer was bound to a uniform slot, or vice versa.
+  BufferUsageMismatch { slot: SharedName, expected: String, actual: String },
+  // A buffer was bound to a slot with a different number of dimensions.
+  BufferShapeMismatch { slot: SharedName, expected: usize, actual: usize },
+  // A buffer was bound to two slots of one dispatch with different
+  // access.
+  BufferAccessConflict {
+    buffer: SharedName,
+    slot: SharedName,
+    other_slot: SharedName,
+  },
+  // A buffer was created with more elements than a shape can hold,
+  // or than fit within the buffer size limits of the device.
+  BufferTooLarge { buffer: SharedName, len: usize },
+  // More values were written to a buffer than it holds.
+  BufferLengthMismatch { buffer: SharedName, expected: usize, actual: usize },
+  // A construct session did not specify its result.
+  MissingConstructResult,
+}
+impl fmt::Display for PewterError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match *self {
+      PewterError::NoAdapter =>
+        write!(f, "No suitable adapter found"),
+      PewterError::UnsupportedFeatures(ref features) =>
+        write!(f, "Unsupported features {:?}", features),
+      PewterError::UnsupportedLimit { name, requested, allowed } =>
+        write!(f, "Limit {} of {} exceeds supported {}",
+          name, requested, allowed),
+      PewterError::RequestDevice(ref err) =>
+        write!(f, "{}", err),
+      PewterError::BufferMap(ref err) =>
+        write!(f, "{}", err),
+
+      PewterError::ShaderFileNameCollision(ref name) =>
+        write!(f, "Shader file {} is already declared", name.as_ref()),
+      PewterError::FunctionNameCollision { ref shader_file, ref function } =>
+        write!(f, "Function {} is already declared in shader file {}",
+          function.as_ref(), shader_file.as_ref()),
+      PewterError::BufferNameCollision { ref shader_file, ref buffer } =>
+        write!(f, "Buffer {} is already declared in shader file {}",
+          buffer.as_ref(), shader_file.as_ref()),
+      PewterError::ArgumentNameCollision { ref function, ref argument } =>
+        write!(f, "Argument {} is already declared in function {}",
+          argument.as_ref(), function.as_ref()),
+      PewterError::UnsupportedEntryCoordinate { ref function, ref data_type } =>
+        write!(f, "Entry function {} has unsupported coordinate type {}",
+          function.as_ref(), data_type),
+      PewterError::ShaderParse { ref shader_file, ref message } =>
+        write!(f, "Failed to parse shader file {}:\n{}",
+          shader_file.as_ref(), message),
+      PewterError::ShaderValidation { ref shader_file, ref message } =>
+        write!(f, "Failed to validate shader file {}:\n{}",
+          shader_file.as_ref(), message),
+      PewterError::UniformLayout { ref buffer, ref data_type, ref field } =>
+        write!(f, "Uniform buffer {} cannot hold {}: field {} breaks the \
+                   uniform layout rules",
+          buffer.as_ref(), data_type, field.as_ref()),
+      PewterError::CallArgumentMismatch {
+        ref function, ref expected, ref actual
+      } =>
+        write!(f, "Function {} takes ({}), called with ({})",
+          function.as_ref(), expected, actual),
+      PewterError::BreakOutsideLoop { ref function } =>
+        write!(f, "Function {} breaks outside of a loop", function.as_ref()),
+      PewterError::ContinueOutsideLoop { ref function } =>
+        write!(f, "Function {} continues outside of a loop",
+          function.as_ref()),
+      PewterError::NonHostShareableBuffer { ref buffer, ref data_type } =>
+        write!(f, "Buffer {} cannot hold {}, which is not host-shareable",
+          buffer.as_ref(), data_type),
+      PewterError::AtomicBufferAccess { ref buffer, ref data_type } =>
+        write!(f, "Buffer {} holds atomics in {}, so must be read-write",
+          buffer.as_ref(), data_type),
+      PewterError::BufferDimensionMismatch { ref buffer, expected, actual } =>
+        write!(f, "Buffer {} is indexed by {}-d coordinates, got {}-d",
+          buffer.as_ref(), expected, actual),
+
+      PewterError::PersistentBufferNameCollision(ref name) =>
+        write!(f, "Persistent buffer {} already exists", name.as_ref()),
+      PewterError::UnknownPersistentBuffer(ref name) =>
+        write!(f, "Unknown persistent buffer {}", name.as_ref()),
+
+      PewterError::UnknownShaderFile(ref name) =>
+        write!(f, "Unknown shader file {}", name.as_ref()),
+      PewterError::UnknownEntryPoint { ref shader_file, ref entry } =>
+        write!(f, "Unknown entrypoint {} in shader file {}",
+          entry, shader_file.as_ref()),
+      PewterError::GridDimensionMismatch { ref entry, expected, actual } =>
+        write!(f, "Entrypoint {} takes a {}-d grid, got {}-d",
+          entry.as_ref(), expected, actual),
+      PewterError::GridTooLarge { ref entry, ref grid, max_workgroups } =>
+        write!(f, "Grid {:?} of entrypoint {} needs more than {} \
+                   workgroups per dimension",
+          grid, entry.as_ref(), max_workgroups),
+      PewterError::UnknownBuffer { ref entry, ref slot } =>
+        write!(f, "Unknown buffer {} bound for entrypoint {}",
+          slot.as_ref(), entry.as_ref()),
+      PewterError::UnboundBuffer { ref entry, ref slot } =>
+        write!(f, "Buffer {} not bound for entrypoint {}",
+          slot.as_ref(), entry.as_ref()),
+      PewterError::BufferTypeMismatch { ref slot, ref expected, ref actual } =>
+        write!(f, "Buffer {} holds {}, got {}",
+          slot.as_ref(), expected, actual),
+      PewterError::BufferUsageMismatch { ref slot, ref expected, ref actual } =>
+        write!(f, "Buffer {} is a {} buffer, got a {} buffer",
+          slot.as_ref(), expected, actual),
+      PewterError::BufferAccessConflict {
+        ref buffer, ref slot, ref other_slot
+      } =>
+        write!(f, "Buffer {} bound to {} is already bound to {}, which \
+                   has a different access",
+          buffer.as_ref(), slot.as_ref(), other_slot.as_ref()),
+      PewterError::BufferTooLarge { ref buffer, len } =>
+        write!(f, "Buffer {} cannot hold {} elements", buffer.as_ref(), len),
+      PewterError::BufferShapeMismatch { ref slot, expected, actual } =>
+        write!(f, "Buffer {} is {}-d, got {}-d",
+          slot.as_ref(), expected, actual),
+      PewterError::BufferLengthMismatch { ref buffer, expected, actual } =>
+        write!(f, "Buffer {} holds {} elements, got {}",
+          buffer.as_ref(), expected, actual),
+      PewterError::MissingConstructResult =>
+        write!(f, "Construct did not specify a result"),
+    }
+  }
+}
+impl std::error::Error for PewterError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match *self {
+      PewterError::RequestDevice(ref err) => Some(err),
+      PewterError::BufferMap(ref err) => Some(err),
+      _ => None,
+    }
+  }
+}
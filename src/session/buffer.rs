@@ -1,34 +1,205 @@
 use std::marker::PhantomData;
 
 use crate::{
-  types::{DataType, DataTypeInfo},
-  session::DeclareSession,
+  PewterError,
+  types::{
+    DataType, DataTypeInfo, NonAtomic, SharedName,
+    data_type_info_for, intern_name,
+  },
+  buf::{
+    BufferCoord,
+    AccessMode,
+    StorageAccess,
+    ReadWriteAccess,
+    UniformAccess,
+  },
+  declare::{DeclareBuffer, DeclareCodeExpr, DeclareUnaryOp},
+  session::SessionCodeExpr,
 };
 
 
 /**
  * Represents a buffer abstraction during a shader function
- * session.
+ * session.  The access mode `A` determines how the buffer can
+ * be used by shader code.
  */
-pub struct SessionBuffer<'a, DT: DataType> {
+pub struct SessionBuffer<'a, DT, A = ReadWriteAccess>
+  where DT: DataType,
+        A: AccessMode,
+{
+  name: SharedName,
   data_type: DataTypeInfo,
+  dims: usize,
   idx: usize,
-  _dummy: PhantomData<&'a DT>,
+  _dummy: PhantomData<(&'a DT, A)>,
 }
-impl<'a, DT: DataType> SessionBuffer<'a, DT> {
+impl<'a, DT, A> SessionBuffer<'a, DT, A>
+  where DT: DataType,
+        A: AccessMode,
+{
   pub(crate) fn new(
-    data_type: DataTypeInfo, 
+    name: SharedName,
+    data_type: DataTypeInfo,
+    dims: usize,
     idx: usize,
   ) -> Self {
-    SessionBuffer { data_type, idx, _dummy: PhantomData }
+    SessionBuffer { name, data_type, dims, idx, _dummy: PhantomData }
+  }
+
+  pub fn dims(&self) -> usize { self.dims }
+
+  pub(crate) fn name(&self) -> &SharedName { &self.name }
+}
+impl<'a, DT, A> SessionBuffer<'a, DT, A>
+  where DT: DataType,
+        A: StorageAccess,
+{
+  /**
+   * The index of the element at `coord`, which must have as many
+   * dimensions as the buffer.
+   */
+  pub fn linear_index<'b, Coord>(&self, coord: SessionCodeExpr<'b, Coord>)
+    -> Result<SessionCodeExpr<'b, u32>, PewterError>
+    where Coord: BufferCoord
+  {
+    if Coord::DIMS != self.dims {
+      return Err(PewterError::BufferDimensionMismatch {
+        buffer: self.name.clone(),
+        expected: self.dims,
+        actual: Coord::DIMS,
+      });
+    }
+    let data_type = data_type_info_for::<u32>();
+    if self.dims == 1 {
+      return Ok(SessionCodeExpr::new(data_type, coord.declaration()));
+    }
+    let helper = intern_name(&DeclareBuffer::index_helper_name(&self.name));
+    let call = DeclareCodeExpr::new_call(helper, vec![coord.declaration()]);
+    Ok(SessionCodeExpr::new(data_type, call))
+  }
+
+  /** Load the element at the linear index `idx`. */
+  pub fn load<'b>(&self, idx: SessionCodeExpr<'b, u32>)
+    -> SessionCodeExpr<'b, DT>
+    where DT: NonAtomic
+  {
+    let index = DeclareCodeExpr::new_index(
+      self.name.clone(),
+      Box::new(idx.declaration()),
+    );
+    SessionCodeExpr::new(self.data_type.clone(), index)
+  }
+}
+impl<'a, DT> SessionBuffer<'a, DT, UniformAccess>
+  where DT: DataType,
+{
+  /** Load the value held by the uniform buffer. */
+  pub fn load_uniform<'b>(&self) -> SessionCodeExpr<'b, DT>
+    where DT: NonAtomic
+  {
+    let global = DeclareCodeExpr::new_global(self.name.clone());
+    SessionCodeExpr::new(self.data_type.clone(), global)
   }
 }
-impl<'a, DT: DataType> Clone for SessionBuffer<'a, DT> {
+impl<'a, DT, A> Clone for SessionBuffer<'a, DT, A>
+  where DT: DataType,
+        A: AccessMode,
+{
   fn clone(&self) -> Self {
     SessionBuffer {
+      name: self.name.clone(),
       data_type: self.data_type.clone(),
+      dims: self.dims,
       idx: self.idx,
       _dummy: PhantomData,
     }
   }
-}
\ No newline at end of file
+}
+
+/**
+ * Represents a tail buffer during a shader function session: a
+ * header of type `H` followed by a runtime-sized array of `DT`.
+ */
+pub struct SessionTailBuffer<'a, H, DT, A = ReadWriteAccess>
+  where H: DataType,
+        DT: DataType,
+        A: StorageAccess,
+{
+  name: SharedName,
+  header_type: DataTypeInfo,
+  data_type: DataTypeInfo,
+  idx: usize,
+  _dummy: PhantomData<(&'a H, &'a DT, A)>,
+}
+impl<'a, H, DT, A> SessionTailBuffer<'a, H, DT, A>
+  where H: DataType,
+        DT: DataType,
+        A: StorageAccess,
+{
+  pub(crate) fn new(
+    name: SharedName,
+    header_type: DataTypeInfo,
+    data_type: DataTypeInfo,
+    idx: usize,
+  ) -> Self {
+    SessionTailBuffer {
+      name, header_type, data_type, idx, _dummy: PhantomData
+    }
+  }
+
+  pub(crate) fn header_path(&self) -> SharedName {
+    intern_name(&DeclareBuffer::header_path(&self.name))
+  }
+  pub(crate) fn items_path(&self) -> SharedName {
+    intern_name(&DeclareBuffer::items_path(&self.name))
+  }
+
+  /** Load the header. */
+  pub fn load_header<'b>(&self) -> SessionCodeExpr<'b, H>
+    where H: NonAtomic
+  {
+    let global = DeclareCodeExpr::new_global(self.header_path());
+    SessionCodeExpr::new(self.header_type.clone(), global)
+  }
+
+  /** Load the element of the array at `idx`. */
+  pub fn load<'b>(&self, idx: SessionCodeExpr<'b, u32>)
+    -> SessionCodeExpr<'b, DT>
+    where DT: NonAtomic
+  {
+    let index = DeclareCodeExpr::new_index(
+      self.items_path(),
+      Box::new(idx.declaration()),
+    );
+    SessionCodeExpr::new(self.data_type.clone(), index)
+  }
+
+  /**
+   * `arrayLength(&buf.items)`: the number of elements which fit in
+   * the array of the bound buffer.
+   */
+  pub fn array_length<'b>(&self) -> SessionCodeExpr<'b, u32> {
+    let items = DeclareCodeExpr::new_global(self.items_path());
+    let pointer = DeclareCodeExpr::new_unary(
+      DeclareUnaryOp::AddressOf,
+      Box::new(items),
+    );
+    let call = DeclareCodeExpr::new_builtin("arrayLength", vec![pointer]);
+    SessionCodeExpr::new(data_type_info_for::<u32>(), call)
+  }
+}
+impl<'a, H, DT, A> Clone for SessionTailBuffer<'a, H, DT, A>
+  where H: DataType,
+        DT: DataType,
+        A: StorageAccess,
+{
+  fn clone(&self) -> Self {
+    SessionTailBuffer {
+      name: self.name.clone(),
+      header_type: self.header_type.clone(),
+      data_type: self.data_type.clone(),
+      idx: self.idx,
+      _dummy: PhantomData,
+    }
+  }
+}
@@ -0,0 +1,279 @@
+use crate::{
+  types::{DataType, VectorType, MatrixType, data_type_info_for},
+  declare::DeclareCodeExpr,
+  session::{SessionCodeExpr, Bitwise, Comparable, Ordered, Logical},
+};
+
+
+/**
+ * `f32` and vectors of `f32`, which support WGSL's floating point
+ * builtins such as `floor`, `sqrt`, `sin` and `mix`.
+ */
+pub trait Float: Ordered {}
+
+/** Vectors of `f32`, which can be normalized and reflected. */
+pub trait FloatVector: Float + VectorType {}
+
+/**
+ * Vectors of numbers, whose dot product is a scalar of their
+ * component type.
+ */
+pub trait NumericVector: Ordered + VectorType {}
+
+/** Matrices with as many rows as columns, which have a determinant. */
+pub trait SquareMatrix: MatrixType {}
+
+impl Float for f32 {}
+impl Float for [f32; 2] {}
+impl Float for [f32; 3] {}
+impl Float for [f32; 4] {}
+
+impl FloatVector for [f32; 2] {}
+impl FloatVector for [f32; 3] {}
+impl FloatVector for [f32; 4] {}
+
+impl NumericVector for [u32; 2] {}
+impl NumericVector for [u32; 3] {}
+impl NumericVector for [u32; 4] {}
+impl NumericVector for [i32; 2] {}
+impl NumericVector for [i32; 3] {}
+impl NumericVector for [i32; 4] {}
+impl NumericVector for [f32; 2] {}
+impl NumericVector for [f32; 3] {}
+impl NumericVector for [f32; 4] {}
+
+impl SquareMatrix for [[f32; 2]; 2] {}
+impl SquareMatrix for [[f32; 3]; 3] {}
+impl SquareMatrix for [[f32; 4]; 4] {}
+
+/* Build the call of the builtin `name` with `args`, of type `DT`. */
+fn builtin<'a, DT: DataType>(
+  name: &'static str,
+  args: Vec<DeclareCodeExpr>,
+) -> SessionCodeExpr<'a, DT> {
+  let expr = DeclareCodeExpr::new_builtin(name, args);
+  SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+}
+
+/* Methods calling builtins which return the type of `self`. */
+macro_rules! builtin_methods {
+  ($(
+    $(#[$doc:meta])*
+    $method:ident => $name:literal ($($arg:ident),*);
+  )*) => {
+    $(
+      $(#[$doc])*
+      pub fn $method(self, $($arg: SessionCodeExpr<'a, DT>),*)
+        -> SessionCodeExpr<'a, DT>
+      {
+        builtin($name, vec![self.declaration(), $($arg.declaration()),*])
+      }
+    )*
+  };
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Ordered,
+{
+  builtin_methods! {
+    /** `abs(self)` */
+    abs => "abs" ();
+    /** `min(self, rhs)` */
+    min => "min" (rhs);
+    /** `max(self, rhs)` */
+    max => "max" (rhs);
+    /** `clamp(self, lo, hi)` */
+    clamp => "clamp" (lo, hi);
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Float,
+{
+  builtin_methods! {
+    /** `floor(self)` */
+    floor => "floor" ();
+    /** `ceil(self)` */
+    ceil => "ceil" ();
+    /** `round(self)`, rounding halves to even. */
+    round => "round" ();
+    /** `fract(self)` */
+    fract => "fract" ();
+    /** `trunc(self)` */
+    trunc => "trunc" ();
+    /** `sign(self)` */
+    sign => "sign" ();
+    /** `saturate(self)`, clamping to between 0 and 1. */
+    saturate => "saturate" ();
+    /** `sqrt(self)` */
+    sqrt => "sqrt" ();
+    /** `inverseSqrt(self)` */
+    inverse_sqrt => "inverseSqrt" ();
+    /** `exp(self)` */
+    exp => "exp" ();
+    /** `exp2(self)` */
+    exp2 => "exp2" ();
+    /** `log(self)` */
+    log => "log" ();
+    /** `log2(self)` */
+    log2 => "log2" ();
+    /** `pow(self, exponent)` */
+    pow => "pow" (exponent);
+    /** `sin(self)` */
+    sin => "sin" ();
+    /** `cos(self)` */
+    cos => "cos" ();
+    /** `tan(self)` */
+    tan => "tan" ();
+    /** `asin(self)` */
+    asin => "asin" ();
+    /** `acos(self)` */
+    acos => "acos" ();
+    /** `atan(self)` */
+    atan => "atan" ();
+    /** `atan2(self, x)`, the angle of the point `(x, self)`. */
+    atan2 => "atan2" (x);
+    /** `sinh(self)` */
+    sinh => "sinh" ();
+    /** `cosh(self)` */
+    cosh => "cosh" ();
+    /** `tanh(self)` */
+    tanh => "tanh" ();
+    /** `radians(self)`, converting from degrees. */
+    radians => "radians" ();
+    /** `degrees(self)`, converting from radians. */
+    degrees => "degrees" ();
+    /** `mix(self, other, t)`, interpolating from `self` to `other`. */
+    mix => "mix" (other, t);
+    /** `fma(self, b, c)`, computing `self * b + c`. */
+    fma => "fma" (b, c);
+  }
+
+  /** `step(edge, self)`: 1 where `self >= edge`, otherwise 0. */
+  pub fn step(self, edge: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT>
+  {
+    builtin("step", vec![edge.declaration(), self.declaration()])
+  }
+  /** `smoothstep(lo, hi, self)` */
+  pub fn smoothstep(self,
+    lo: SessionCodeExpr<'a, DT>,
+    hi: SessionCodeExpr<'a, DT>,
+  ) -> SessionCodeExpr<'a, DT> {
+    builtin("smoothstep", vec![
+      lo.declaration(),
+      hi.declaration(),
+      self.declaration(),
+    ])
+  }
+  /** `length(self)` */
+  pub fn length(self) -> SessionCodeExpr<'a, f32> {
+    builtin("length", vec![self.declaration()])
+  }
+  /** `distance(self, rhs)` */
+  pub fn distance(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, f32>
+  {
+    builtin("distance", vec![self.declaration(), rhs.declaration()])
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: FloatVector,
+{
+  builtin_methods! {
+    /** `normalize(self)` */
+    normalize => "normalize" ();
+    /** `reflect(self, normal)` */
+    reflect => "reflect" (normal);
+  }
+}
+
+impl<'a> SessionCodeExpr<'a, [f32; 3]> {
+  /** `cross(self, rhs)` */
+  pub fn cross(self, rhs: SessionCodeExpr<'a, [f32; 3]>)
+    -> SessionCodeExpr<'a, [f32; 3]>
+  {
+    builtin("cross", vec![self.declaration(), rhs.declaration()])
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: NumericVector,
+{
+  /** `dot(self, rhs)` */
+  pub fn dot(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Field>
+  {
+    builtin("dot", vec![self.declaration(), rhs.declaration()])
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Bitwise,
+{
+  builtin_methods! {
+    /** `countOneBits(self)` */
+    count_one_bits => "countOneBits" ();
+    /** `reverseBits(self)` */
+    reverse_bits => "reverseBits" ();
+    /**
+     * `firstLeadingBit(self)`: the index of the most significant
+     * bit set, or of the most significant bit differing from the
+     * sign for signed integers.  All bits are set if there is none.
+     */
+    first_leading_bit => "firstLeadingBit" ();
+    /**
+     * `firstTrailingBit(self)`: the index of the least significant
+     * bit set.  All bits are set if there is none.
+     */
+    first_trailing_bit => "firstTrailingBit" ();
+  }
+}
+
+impl<'a, M> SessionCodeExpr<'a, M>
+  where M: MatrixType,
+{
+  /** `transpose(self)`, swapping the rows and columns. */
+  pub fn transpose(self) -> SessionCodeExpr<'a, M::Transposed> {
+    builtin("transpose", vec![self.declaration()])
+  }
+}
+
+impl<'a, M> SessionCodeExpr<'a, M>
+  where M: SquareMatrix,
+{
+  /** `determinant(self)` */
+  pub fn determinant(self) -> SessionCodeExpr<'a, f32> {
+    builtin("determinant", vec![self.declaration()])
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Logical + VectorType,
+{
+  /** `any(self)`: whether any component is true. */
+  pub fn any(self) -> SessionCodeExpr<'a, bool> {
+    builtin("any", vec![self.declaration()])
+  }
+  /** `all(self)`: whether every component is true. */
+  pub fn all(self) -> SessionCodeExpr<'a, bool> {
+    builtin("all", vec![self.declaration()])
+  }
+}
+
+/**
+ * `select(if_false, if_true, cond)`.  Vectors are selected
+ * component-wise by a vector of `bool`.
+ */
+pub fn select<'a, DT: Comparable>(
+  if_false: SessionCodeExpr<'a, DT>,
+  if_true: SessionCodeExpr<'a, DT>,
+  cond: SessionCodeExpr<'a, DT::Bool>,
+) -> SessionCodeExpr<'a, DT> {
+  builtin("select", vec![
+    if_false.declaration(),
+    if_true.declaration(),
+    cond.declaration(),
+  ])
+}
@@ -1,11 +1,13 @@
 use std::marker::PhantomData;
 use crate::{
   types::{
+    ArrayType,
     DataType,
     DataTypeInfo,
-    PrimType, VectorType, ScalarType,
+    DataTypeTuple,
+    NonAtomic,
+    data_type_info_for,
   },
-  session::DeclareSession,
   declare::DeclareCodeExpr,
 };
 
@@ -13,6 +15,7 @@ use crate::{
 /**
  * Representation of an expression built during a session.
  */
+#[derive(Clone)]
 pub struct SessionCodeExpr<'a, DT: DataType> {
   data_type: DataTypeInfo,
   declaration: DeclareCodeExpr,
@@ -30,65 +33,99 @@ impl<'a, DT: DataType> SessionCodeExpr<'a, DT> {
     }
   }
 
+  /** The type of the value the expression computes. */
+  pub fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+
   pub(crate) fn declaration(self) -> DeclareCodeExpr {
     self.declaration
   }
 }
 
-pub trait AddableTo<R: DataType>: DataType {
-  type Result: DataType;
-}
-impl AddableTo<u32> for u32 {
-  type Result = u32;
-}
-impl AddableTo<i32> for i32 {
-  type Result = i32;
-}
-impl AddableTo<f32> for f32 {
-  type Result = f32;
-}
-impl AddableTo<[u32; 2]> for [u32; 2] {
-  type Result = [u32; 2];
-}
-impl AddableTo<[u32; 3]> for [u32; 3] {
-  type Result = [u32; 3];
-}
-impl AddableTo<[u32; 4]> for [u32; 4] {
-  type Result = [u32; 4];
-}
-impl AddableTo<[i32; 2]> for [i32; 2] {
-  type Result = [i32; 2];
+impl<'a, AT: ArrayType> SessionCodeExpr<'a, AT> {
+  /**
+   * The element of the array at `index`.  WGSL only allows indices
+   * which vary at runtime into arrays held in buffers or variables,
+   * so an array computed or bound with `let_` takes constant indices.
+   */
+  pub fn index(self, index: SessionCodeExpr<'a, u32>)
+    -> SessionCodeExpr<'a, AT::Element>
+  {
+    let expr = DeclareCodeExpr::new_element(
+      Box::new(self.declaration()),
+      Box::new(index.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<AT::Element>(), expr)
+  }
 }
-impl AddableTo<[i32; 3]> for [i32; 3] {
-  type Result = [i32; 3];
+
+/**
+ * A constant value of any data type, such as `lit(3u32)`,
+ * `lit([0.0f32; 4])` or a record.
+ */
+pub fn lit<'a, DT: NonAtomic>(value: DT) -> SessionCodeExpr<'a, DT> {
+  let expr = DeclareCodeExpr::new_literal(&value);
+  SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
 }
-impl AddableTo<[i32; 4]> for [i32; 4] {
-  type Result = [i32; 4];
+
+/**
+ * Tuples of expressions, used to pass arguments to function calls.
+ * A single expression is a tuple of one.
+ */
+pub trait SessionCodeExprTuple<'a> {
+  type Types: DataTypeTuple;
+  #[doc(hidden)]
+  fn push_args(self, args: &mut CallArgs);
 }
-impl AddableTo<[f32; 2]> for [f32; 2] {
-  type Result = [f32; 2];
+
+/**
+ * The argument expressions of a call, unwrapped from a tuple.  It
+ * can't be named outside the crate, which keeps the tuple trait from
+ * being implemented elsewhere.
+ */
+pub struct CallArgs {
+  exprs: Vec<DeclareCodeExpr>,
 }
-impl AddableTo<[f32; 3]> for [f32; 3] {
-  type Result = [f32; 3];
+impl CallArgs {
+  pub(crate) fn collect<'a, Args>(args: Args) -> Vec<DeclareCodeExpr>
+    where Args: SessionCodeExprTuple<'a>
+  {
+    let mut call_args = CallArgs { exprs: Vec::new() };
+    args.push_args(&mut call_args);
+    call_args.exprs
+  }
 }
-impl AddableTo<[f32; 4]> for [f32; 4] {
-  type Result = [f32; 4];
+
+impl<'a> SessionCodeExprTuple<'a> for () {
+  type Types = ();
+  fn push_args(self, _args: &mut CallArgs) {}
 }
 
-impl<'a, L, R> std::ops::Add<SessionCodeExpr<'a, R>>
-  for SessionCodeExpr<'a, L>
-  where L: AddableTo<R>,
-        R: DataType,
-{
-  type Output = SessionCodeExpr<'a, <L as AddableTo<R>>::Result>;
-  fn add(self, rhs: SessionCodeExpr<'a, R>) -> Self::Output {
-    debug_assert!(self.data_type == u32::PRIM_INFO.into_data_type());
-    debug_assert!(self.data_type == rhs.data_type);
-    let add_expr =
-      DeclareCodeExpr::new_add(
-        Box::new(self.declaration),
-        Box::new(rhs.declaration)
-      );
-    SessionCodeExpr::new(self.data_type, add_expr)
+impl<'a, T0: DataType> SessionCodeExprTuple<'a> for SessionCodeExpr<'a, T0> {
+  type Types = T0;
+  fn push_args(self, args: &mut CallArgs) {
+    args.exprs.push(self.declaration());
   }
-}
\ No newline at end of file
+}
+
+macro_rules! impl_code_expr_tuple {
+  ($($name:ident : $ty:ident),*) => {
+    impl<'a, $($ty: DataType),*> SessionCodeExprTuple<'a>
+      for ($(SessionCodeExpr<'a, $ty>,)*)
+    {
+      type Types = ($($ty,)*);
+      fn push_args(self, args: &mut CallArgs) {
+        let ($($name,)*) = self;
+        $(args.exprs.push($name.declaration());)*
+      }
+    }
+  };
+}
+impl_code_expr_tuple!(e0: T0, e1: T1);
+impl_code_expr_tuple!(e0: T0, e1: T1, e2: T2);
+impl_code_expr_tuple!(e0: T0, e1: T1, e2: T2, e3: T3);
+impl_code_expr_tuple!(e0: T0, e1: T1, e2: T2, e3: T3, e4: T4);
+impl_code_expr_tuple!(e0: T0, e1: T1, e2: T2, e3: T3, e4: T4, e5: T5);
+impl_code_expr_tuple!(e0: T0, e1: T1, e2: T2, e3: T3, e4: T4, e5: T5, e6: T6);
+impl_code_expr_tuple!(
+  e0: T0, e1: T1, e2: T2, e3: T3, e4: T4, e5: T5, e6: T6, e7: T7
+);
@@ -0,0 +1,398 @@
+use crate::{
+  types::{
+    DataType,
+    DataTypeInfo,
+    PrimTypeInfo,
+    data_type_info_for,
+  },
+  declare::{DeclareCodeExpr, DeclareBinaryOp, DeclareUnaryOp},
+  session::SessionCodeExpr,
+};
+
+
+/**
+ * Types which support the arithmetic operators `+`, `-`, `*`, `/`
+ * and `%` with `R`, giving `Result`.  As in WGSL, both sides are
+ * scalars or vectors of the same type, or one side is a vector and
+ * the other a scalar of its component type.
+ */
+pub trait ArithmeticWith<R: DataType>: DataType {
+  type Result: DataType;
+}
+
+/**
+ * Types which support `+` and `-` with `R`, giving `Result`: the
+ * scalars and vectors of `ArithmeticWith`, and matrices of the same
+ * shape.
+ */
+pub trait AddWith<R: DataType>: DataType {
+  type Result: DataType;
+}
+
+/**
+ * Types which support `*` with `R`, giving `Result`: the scalars
+ * and vectors of `ArithmeticWith`, and matrices.  Matrices scale by
+ * `f32`, and multiply with vectors and matrices of matching size as
+ * in linear algebra.
+ */
+pub trait MultiplyWith<R: DataType>: DataType {
+  type Result: DataType;
+}
+
+/** Types which support the negation operator. */
+pub trait Negatable: DataType {}
+
+/**
+ * Integer scalars and vectors, which support the bitwise operators
+ * `&`, `|`, `^` and `!` with operands of the same type.  `!` is
+ * WGSL's complement operator `~`.
+ */
+pub trait Bitwise: DataType {}
+
+/**
+ * Integer types which can be shifted by `R`.  As in WGSL, shift
+ * amounts are `u32`, or vectors of `u32` for vector operands.
+ */
+pub trait ShiftableBy<R: DataType>: Bitwise {}
+
+/**
+ * Types which can be compared for equality with `eq` and `ne`,
+ * giving `Bool`: a `bool` for scalars, or a vector of `bool` with
+ * one component per component compared.
+ */
+pub trait Comparable: DataType {
+  type Bool: Logical;
+}
+
+/**
+ * Numeric types, which can also be ordered with `lt`, `le`, `gt`
+ * and `ge`.
+ */
+pub trait Ordered: Comparable {}
+
+/**
+ * Integer scalars, which can count through a range with
+ * `for_range`.
+ */
+pub trait LoopIndex: Ordered {}
+
+/**
+ * `bool` and vectors of `bool`, which can be combined with `and`,
+ * `or` and `not`.  Vectors are combined component-wise.
+ */
+pub trait Logical: Comparable {}
+
+macro_rules! impl_arithmetic {
+  ($scalar:ty) => {
+    impl ArithmeticWith<$scalar> for $scalar {
+      type Result = $scalar;
+    }
+    impl_arithmetic!($scalar, [$scalar; 2]);
+    impl_arithmetic!($scalar, [$scalar; 3]);
+    impl_arithmetic!($scalar, [$scalar; 4]);
+  };
+  ($scalar:ty, $vector:ty) => {
+    impl ArithmeticWith<$vector> for $vector {
+      type Result = $vector;
+    }
+    impl ArithmeticWith<$scalar> for $vector {
+      type Result = $vector;
+    }
+    impl ArithmeticWith<$vector> for $scalar {
+      type Result = $vector;
+    }
+  };
+}
+impl_arithmetic!(u32);
+impl_arithmetic!(i32);
+impl_arithmetic!(f32);
+
+impl<L: ArithmeticWith<R>, R: DataType> AddWith<R> for L {
+  type Result = L::Result;
+}
+impl<L: ArithmeticWith<R>, R: DataType> MultiplyWith<R> for L {
+  type Result = L::Result;
+}
+
+macro_rules! impl_matrix_arithmetic {
+  ($cols:literal, $rows:literal) => {
+    impl AddWith<[[f32; $rows]; $cols]> for [[f32; $rows]; $cols] {
+      type Result = [[f32; $rows]; $cols];
+    }
+    impl MultiplyWith<f32> for [[f32; $rows]; $cols] {
+      type Result = [[f32; $rows]; $cols];
+    }
+    impl MultiplyWith<[[f32; $rows]; $cols]> for f32 {
+      type Result = [[f32; $rows]; $cols];
+    }
+    // A column vector on the right, or a row vector on the left.
+    impl MultiplyWith<[f32; $cols]> for [[f32; $rows]; $cols] {
+      type Result = [f32; $rows];
+    }
+    impl MultiplyWith<[[f32; $rows]; $cols]> for [f32; $rows] {
+      type Result = [f32; $cols];
+    }
+    impl_matrix_arithmetic!($cols, $rows, 2);
+    impl_matrix_arithmetic!($cols, $rows, 3);
+    impl_matrix_arithmetic!($cols, $rows, 4);
+  };
+  ($cols:literal, $rows:literal, $rhs_cols:literal) => {
+    impl MultiplyWith<[[f32; $cols]; $rhs_cols]> for [[f32; $rows]; $cols] {
+      type Result = [[f32; $rows]; $rhs_cols];
+    }
+  };
+}
+impl_matrix_arithmetic!(2, 2);
+impl_matrix_arithmetic!(2, 3);
+impl_matrix_arithmetic!(2, 4);
+impl_matrix_arithmetic!(3, 2);
+impl_matrix_arithmetic!(3, 3);
+impl_matrix_arithmetic!(3, 4);
+impl_matrix_arithmetic!(4, 2);
+impl_matrix_arithmetic!(4, 3);
+impl_matrix_arithmetic!(4, 4);
+
+macro_rules! impl_bitwise {
+  ($($ty:ty => $shift:ty),*) => {
+    $(
+      impl Bitwise for $ty {}
+      impl ShiftableBy<$shift> for $ty {}
+    )*
+  };
+}
+impl_bitwise!(
+  u32 => u32, [u32; 2] => [u32; 2], [u32; 3] => [u32; 3], [u32; 4] => [u32; 4],
+  i32 => u32, [i32; 2] => [u32; 2], [i32; 3] => [u32; 3], [i32; 4] => [u32; 4]
+);
+
+macro_rules! impl_comparable {
+  ($($ty:ty => $bool:ty),*) => {
+    $(
+      impl Comparable for $ty {
+        type Bool = $bool;
+      }
+    )*
+  };
+}
+impl_comparable!(
+  u32 => bool, [u32; 2] => [bool; 2],
+  [u32; 3] => [bool; 3], [u32; 4] => [bool; 4],
+  i32 => bool, [i32; 2] => [bool; 2],
+  [i32; 3] => [bool; 3], [i32; 4] => [bool; 4],
+  f32 => bool, [f32; 2] => [bool; 2],
+  [f32; 3] => [bool; 3], [f32; 4] => [bool; 4],
+  bool => bool, [bool; 2] => [bool; 2],
+  [bool; 3] => [bool; 3], [bool; 4] => [bool; 4]
+);
+
+impl Ordered for u32 {}
+impl Ordered for [u32; 2] {}
+impl Ordered for [u32; 3] {}
+impl Ordered for [u32; 4] {}
+impl Ordered for i32 {}
+impl Ordered for [i32; 2] {}
+impl Ordered for [i32; 3] {}
+impl Ordered for [i32; 4] {}
+impl Ordered for f32 {}
+impl Ordered for [f32; 2] {}
+impl Ordered for [f32; 3] {}
+impl Ordered for [f32; 4] {}
+
+impl LoopIndex for u32 {}
+impl LoopIndex for i32 {}
+
+impl Logical for bool {}
+impl Logical for [bool; 2] {}
+impl Logical for [bool; 3] {}
+impl Logical for [bool; 4] {}
+
+impl Negatable for i32 {}
+impl Negatable for [i32; 2] {}
+impl Negatable for [i32; 3] {}
+impl Negatable for [i32; 4] {}
+impl Negatable for f32 {}
+impl Negatable for [f32; 2] {}
+impl Negatable for [f32; 3] {}
+impl Negatable for [f32; 4] {}
+
+/* Build the expression `lhs op rhs` of type `DT`. */
+fn binary<'a, DT: DataType>(
+  op: DeclareBinaryOp,
+  lhs: DeclareCodeExpr,
+  rhs: DeclareCodeExpr,
+) -> SessionCodeExpr<'a, DT> {
+  let expr = DeclareCodeExpr::new_binary(op, Box::new(lhs), Box::new(rhs));
+  SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+}
+
+macro_rules! impl_arithmetic_op {
+  ($with:ident, $trait:ident, $method:ident, $op:ident) => {
+    impl<'a, L, R> std::ops::$trait<SessionCodeExpr<'a, R>>
+      for SessionCodeExpr<'a, L>
+      where L: $with<R>,
+            R: DataType,
+    {
+      type Output = SessionCodeExpr<'a, <L as $with<R>>::Result>;
+      fn $method(self, rhs: SessionCodeExpr<'a, R>) -> Self::Output {
+        binary(DeclareBinaryOp::$op, self.declaration(), rhs.declaration())
+      }
+    }
+  };
+}
+impl_arithmetic_op!(AddWith, Add, add, Add);
+impl_arithmetic_op!(AddWith, Sub, sub, Sub);
+impl_arithmetic_op!(MultiplyWith, Mul, mul, Mul);
+impl_arithmetic_op!(ArithmeticWith, Div, div, Div);
+impl_arithmetic_op!(ArithmeticWith, Rem, rem, Rem);
+
+impl<'a, DT> std::ops::Neg for SessionCodeExpr<'a, DT>
+  where DT: Negatable,
+{
+  type Output = SessionCodeExpr<'a, DT>;
+  fn neg(self) -> Self::Output {
+    let expr = DeclareCodeExpr::new_unary(
+      DeclareUnaryOp::Neg,
+      Box::new(self.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+  }
+}
+
+macro_rules! impl_bitwise_op {
+  ($trait:ident, $method:ident, $op:ident) => {
+    impl<'a, DT> std::ops::$trait<SessionCodeExpr<'a, DT>>
+      for SessionCodeExpr<'a, DT>
+      where DT: Bitwise,
+    {
+      type Output = SessionCodeExpr<'a, DT>;
+      fn $method(self, rhs: SessionCodeExpr<'a, DT>) -> Self::Output {
+        binary(DeclareBinaryOp::$op, self.declaration(), rhs.declaration())
+      }
+    }
+  };
+}
+impl_bitwise_op!(BitAnd, bitand, BitAnd);
+impl_bitwise_op!(BitOr, bitor, BitOr);
+impl_bitwise_op!(BitXor, bitxor, BitXor);
+
+macro_rules! impl_shift_op {
+  ($trait:ident, $method:ident, $op:ident) => {
+    impl<'a, L, R> std::ops::$trait<SessionCodeExpr<'a, R>>
+      for SessionCodeExpr<'a, L>
+      where L: ShiftableBy<R>,
+            R: DataType,
+    {
+      type Output = SessionCodeExpr<'a, L>;
+      fn $method(self, rhs: SessionCodeExpr<'a, R>) -> Self::Output {
+        binary(DeclareBinaryOp::$op, self.declaration(), rhs.declaration())
+      }
+    }
+  };
+}
+impl_shift_op!(Shl, shl, Shl);
+impl_shift_op!(Shr, shr, Shr);
+
+impl<'a, DT> std::ops::Not for SessionCodeExpr<'a, DT>
+  where DT: Bitwise,
+{
+  type Output = SessionCodeExpr<'a, DT>;
+  fn not(self) -> Self::Output {
+    let expr = DeclareCodeExpr::new_unary(
+      DeclareUnaryOp::BitNot,
+      Box::new(self.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Comparable,
+{
+  /** `self == rhs` */
+  pub fn eq(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Eq, self.declaration(), rhs.declaration())
+  }
+  /** `self != rhs` */
+  pub fn ne(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Ne, self.declaration(), rhs.declaration())
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Ordered,
+{
+  /** `self < rhs` */
+  pub fn lt(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Lt, self.declaration(), rhs.declaration())
+  }
+  /** `self <= rhs` */
+  pub fn le(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Le, self.declaration(), rhs.declaration())
+  }
+  /** `self > rhs` */
+  pub fn gt(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Gt, self.declaration(), rhs.declaration())
+  }
+  /** `self >= rhs` */
+  pub fn ge(self, rhs: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT::Bool>
+  {
+    binary(DeclareBinaryOp::Ge, self.declaration(), rhs.declaration())
+  }
+}
+
+impl<'a, DT> SessionCodeExpr<'a, DT>
+  where DT: Logical,
+{
+  /**
+   * Both `self` and `rhs`.  WGSL's `&&` only takes scalars, so
+   * vectors use the component-wise `&`.
+   */
+  pub fn and(self, rhs: SessionCodeExpr<'a, DT>) -> SessionCodeExpr<'a, DT> {
+    let op = if is_scalar::<DT>() {
+      DeclareBinaryOp::And
+    } else {
+      DeclareBinaryOp::BitAnd
+    };
+    binary(op, self.declaration(), rhs.declaration())
+  }
+  /**
+   * Either `self` or `rhs`.  WGSL's `||` only takes scalars, so
+   * vectors use the component-wise `|`.
+   */
+  pub fn or(self, rhs: SessionCodeExpr<'a, DT>) -> SessionCodeExpr<'a, DT> {
+    let op = if is_scalar::<DT>() {
+      DeclareBinaryOp::Or
+    } else {
+      DeclareBinaryOp::BitOr
+    };
+    binary(op, self.declaration(), rhs.declaration())
+  }
+  /**
+   * `!self`.  The `!` operator on expressions is already the
+   * integer complement, so logical negation is a method.
+   */
+  #[allow(clippy::should_implement_trait)]
+  pub fn not(self) -> SessionCodeExpr<'a, DT> {
+    let expr = DeclareCodeExpr::new_unary(
+      DeclareUnaryOp::Not,
+      Box::new(self.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+  }
+}
+
+fn is_scalar<DT: DataType>() -> bool {
+  matches!(data_type_info_for::<DT>(),
+    DataTypeInfo::Prim(PrimTypeInfo::Scalar(_)))
+}
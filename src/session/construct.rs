@@ -1,27 +1,315 @@
-use std::marker::PhantomData;
-
 use crate::{
   Pewter,
   pewter::PewterConstruct,
   PewterError,
+  types::{DataType, data_type_info_for, intern_name, SharedName},
+  buf::{
+    Buffer,
+    BufferShape,
+    TypedBuffer,
+    TailBuffer,
+    buffer_type_name,
+    encode_slice,
+  },
+  construct::{
+    ConstructStep,
+    DispatchStep,
+    ConstructOutputs,
+    ConstructReadback,
+    ConstructTailReadback,
+    ConstructResultFn,
+  },
 };
 
 /**
  * An API to perform a sequence of operations on a pewter
- * instance. 
+ * instance.
  */
 pub struct ConstructSession<'a, T: 'static> {
   pewter: &'a Pewter,
-  _dummy: PhantomData<T>,
+  steps: Vec<ConstructStep>,
+  num_readbacks: usize,
+  result: Option<ConstructResultFn<T>>,
 }
 impl<'a, T: 'static> ConstructSession<'a, T> {
   pub(crate) fn new(pewter: &'a Pewter) -> Self {
-    ConstructSession { pewter, _dummy: PhantomData }
+    ConstructSession {
+      pewter,
+      steps: Vec::new(),
+      num_readbacks: 0,
+      result: None,
+    }
+  }
+
+  /**
+   * Create a buffer of `len` elements which lives as long as
+   * the construct.
+   */
+  pub fn buffer<DT>(&mut self, name: &str, len: usize)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let extent = u32::try_from(len).map_err(|_| {
+      PewterError::BufferTooLarge { buffer: intern_name(name), len }
+    }) ?;
+    self.shaped_buffer(name, BufferShape::new_1d(extent))
+  }
+
+  /**
+   * Create a buffer with a 1d, 2d or 3d shape which lives as long
+   * as the construct.
+   */
+  pub fn shaped_buffer<DT>(&mut self, name: &str, shape: BufferShape)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let name = intern_name(name);
+    let data_type = data_type_info_for::<DT>();
+    let buffer = Buffer::new(
+      self.pewter.device(),
+      self.pewter.queue(),
+      name,
+      data_type,
+      shape,
+    ) ?;
+    Ok(TypedBuffer::new(buffer))
+  }
+
+  /**
+   * Create a uniform buffer holding a single value, which lives as
+   * long as the construct.
+   */
+  pub fn uniform_buffer<DT>(&mut self, name: &str)
+    -> Result<TypedBuffer<DT>, PewterError>
+    where DT: DataType
+  {
+    let name = intern_name(name);
+    let data_type = data_type_info_for::<DT>();
+    let buffer = Buffer::new_uniform(
+      self.pewter.device(),
+      self.pewter.queue(),
+      name,
+      data_type,
+    ) ?;
+    Ok(TypedBuffer::new(buffer))
+  }
+
+  /**
+   * Create a buffer holding a header of type `H` followed by an
+   * array of `len` elements, which lives as long as the construct.
+   * The array always holds at least one element, so a `len` of 0
+   * creates a buffer whose `len()` is 1.
+   */
+  pub fn tail_buffer<H, DT>(&mut self, name: &str, len: usize)
+    -> Result<TailBuffer<H, DT>, PewterError>
+    where H: DataType,
+          DT: DataType,
+  {
+    let name = intern_name(name);
+    let buffer = Buffer::new_tail(
+      self.pewter.device(),
+      self.pewter.queue(),
+      name,
+      data_type_info_for::<H>(),
+      data_type_info_for::<DT>(),
+      len,
+    ) ?;
+    Ok(TailBuffer::new(buffer))
+  }
+
+  /** Write `values` into the start of `buffer`. */
+  pub fn write_buffer<DT>(&mut self,
+    buffer: &TypedBuffer<DT>,
+    values: &[DT],
+  ) -> Result<(), PewterError>
+    where DT: DataType
+  {
+    if values.len() > buffer.len() {
+      return Err(PewterError::BufferLengthMismatch {
+        buffer: buffer.name().clone(),
+        expected: buffer.len(),
+        actual: values.len(),
+      });
+    }
+    if values.is_empty() {
+      return Ok(());
+    }
+    let stride = data_type_info_for::<DT>().array_stride();
+    let mut bytes = vec![0_u8; values.len() * stride];
+    encode_slice(values, &mut bytes);
+    let buffer = buffer.untyped().clone();
+    self.steps.push(ConstructStep::Write { buffer, bytes });
+    Ok(())
+  }
+
+  /**
+   * Write `header`, and `values` into the start of the array of
+   * `buffer`.  The rest of the array is zeroed.
+   */
+  pub fn write_tail_buffer<H, DT>(&mut self,
+    buffer: &TailBuffer<H, DT>,
+    header: &H,
+    values: &[DT],
+  ) -> Result<(), PewterError>
+    where H: DataType,
+          DT: DataType,
+  {
+    let bytes = buffer.encode(header, values) ?;
+    let buffer = buffer.untyped().clone();
+    self.steps.push(ConstructStep::Write { buffer, bytes });
+    Ok(())
+  }
+
+  /**
+   * Dispatch the entrypoint `entry` of a declared shader file
+   * over a grid.  The returned handle is used to bind buffers to
+   * the slots declared by the shader file.
+   */
+  pub fn dispatch(&mut self,
+    shader_file: &SharedName,
+    entry: &str,
+    grid: &[u32],
+  ) -> Result<ConstructDispatch<'_>, PewterError> {
+    let dispatch = self.pewter.prepare_dispatch(shader_file, entry, grid) ?;
+    self.steps.push(ConstructStep::Dispatch(dispatch));
+    match self.steps.last_mut() {
+      Some(&mut ConstructStep::Dispatch(ref mut dispatch)) => {
+        Ok(ConstructDispatch { dispatch })
+      },
+      _ => unreachable!(),
+    }
+  }
+
+  /**
+   * Read back the contents of `buffer` at this point in the
+   * construct.
+   */
+  pub fn read_buffer<DT>(&mut self, buffer: &TypedBuffer<DT>)
+    -> ConstructReadback<DT>
+    where DT: DataType
+  {
+    let idx = self.num_readbacks;
+    self.num_readbacks += 1;
+    let buffer = buffer.untyped().clone();
+    let len = buffer.len();
+    self.steps.push(ConstructStep::Read { buffer });
+    ConstructReadback::new(idx, len)
+  }
+
+  /**
+   * Read back the header and array of `buffer` at this point in the
+   * construct.
+   */
+  pub fn read_tail_buffer<H, DT>(&mut self, buffer: &TailBuffer<H, DT>)
+    -> ConstructTailReadback<H, DT>
+    where H: DataType,
+          DT: DataType,
+  {
+    let idx = self.num_readbacks;
+    self.num_readbacks += 1;
+    let buffer = buffer.untyped().clone();
+    let len = buffer.len();
+    self.steps.push(ConstructStep::Read { buffer });
+    ConstructTailReadback::new(idx, len)
   }
 
-  pub(crate) fn make_construct(&self)
+  /**
+   * Specify how the result of the construct is computed from
+   * the buffer contents read back while performing it.
+   */
+  pub fn result<F>(&mut self, func: F)
+    where F: 'static + Fn (&ConstructOutputs) -> T
+  {
+    self.result = Some(Box::new(func));
+  }
+
+  pub(crate) fn make_construct(self)
     -> Result<PewterConstruct<'a, T>, PewterError>
   {
-    panic!("Implement.");
+    let ConstructSession { pewter, mut steps, result, .. } = self;
+    for step in steps.iter_mut() {
+      if let &mut ConstructStep::Dispatch(ref mut dispatch) = step {
+        if let Some(slot) = dispatch.first_unbound() {
+          return Err(PewterError::UnboundBuffer {
+            entry: dispatch.entry().clone(),
+            slot: slot.clone(),
+          });
+        }
+        dispatch.create_bind_groups(pewter.device());
+      }
+    }
+    let result = result.ok_or(PewterError::MissingConstructResult) ?;
+    Ok(PewterConstruct::new(pewter, steps, result))
+  }
+}
+
+/**
+ * A dispatch recorded in a `ConstructSession`, to which buffers
+ * are bound.
+ */
+pub struct ConstructDispatch<'b> {
+  dispatch: &'b mut DispatchStep,
+}
+impl<'b> ConstructDispatch<'b> {
+  /** Bind `buffer` to the shader file's buffer slot `slot`. */
+  pub fn bind<DT>(&mut self, slot: &str, buffer: &TypedBuffer<DT>)
+    -> Result<&mut Self, PewterError>
+    where DT: DataType
+  {
+    self.bind_untyped(slot, buffer.untyped())
   }
-}
\ No newline at end of file
+
+  /** Bind the tail buffer `buffer` to the buffer slot `slot`. */
+  pub fn bind_tail<H, DT>(&mut self, slot: &str, buffer: &TailBuffer<H, DT>)
+    -> Result<&mut Self, PewterError>
+    where H: DataType,
+          DT: DataType,
+  {
+    self.bind_untyped(slot, buffer.untyped())
+  }
+
+  fn bind_untyped(&mut self, slot: &str, buffer: &Buffer)
+    -> Result<&mut Self, PewterError>
+  {
+    let idx = self.dispatch.slot_index(slot).ok_or_else(|| {
+      PewterError::UnknownBuffer {
+        entry: self.dispatch.entry().clone(),
+        slot: intern_name(slot),
+      }
+    }) ?;
+    let decl_slot = self.dispatch.slot(idx);
+    if decl_slot.data_type() != buffer.data_type() ||
+       decl_slot.header() != buffer.header()
+    {
+      return Err(PewterError::BufferTypeMismatch {
+        slot: intern_name(slot),
+        expected: buffer_type_name(decl_slot.header(), decl_slot.data_type()),
+        actual: buffer_type_name(buffer.header(), buffer.data_type()),
+      });
+    }
+    if decl_slot.access().is_uniform() != buffer.is_uniform() {
+      let kind = |uniform| if uniform { "uniform" } else { "storage" };
+      return Err(PewterError::BufferUsageMismatch {
+        slot: intern_name(slot),
+        expected: kind(decl_slot.access().is_uniform()).to_owned(),
+        actual: kind(buffer.is_uniform()).to_owned(),
+      });
+    }
+    if decl_slot.dims() != buffer.shape().dims() {
+      return Err(PewterError::BufferShapeMismatch {
+        slot: intern_name(slot),
+        expected: decl_slot.dims(),
+        actual: buffer.shape().dims(),
+      });
+    }
+    if let Some(other) = self.dispatch.conflicting_slot(idx, buffer) {
+      return Err(PewterError::BufferAccessConflict {
+        buffer: buffer.name().clone(),
+        slot: intern_name(slot),
+        other_slot: other.clone(),
+      });
+    }
+    self.dispatch.bind(idx, buffer.clone());
+    Ok(self)
+  }
+}
@@ -0,0 +1,80 @@
+use crate::{
+  types::{PrimType, data_type_info_for},
+  declare::DeclareCodeExpr,
+  session::SessionCodeExpr,
+};
+
+
+/**
+ * Scalars and vectors which can be converted to `T` with `cast`.  As
+ * in WGSL, any scalar converts to any other, and vectors convert to
+ * vectors with the same number of components.
+ */
+pub trait ConvertibleTo<T: PrimType>: PrimType {}
+
+/**
+ * Numeric scalars and vectors whose bits can be reinterpreted as `T`
+ * with `bitcast`.  Both types have the same `size()`: scalars are
+ * reinterpreted as scalars, and vectors as vectors with the same
+ * number of components.
+ */
+pub trait BitcastTo<T: PrimType>: PrimType {}
+
+macro_rules! impl_convertible {
+  ($from:ty => $($to:ty),*) => {
+    $(
+      impl ConvertibleTo<$to> for $from {}
+      impl ConvertibleTo<[$to; 2]> for [$from; 2] {}
+      impl ConvertibleTo<[$to; 3]> for [$from; 3] {}
+      impl ConvertibleTo<[$to; 4]> for [$from; 4] {}
+    )*
+  };
+}
+impl_convertible!(u32 => u32, i32, f32, bool);
+impl_convertible!(i32 => u32, i32, f32, bool);
+impl_convertible!(f32 => u32, i32, f32, bool);
+impl_convertible!(bool => u32, i32, f32, bool);
+
+macro_rules! impl_bitcast {
+  ($from:ty => $($to:ty),*) => {
+    $(
+      impl BitcastTo<$to> for $from {}
+      impl BitcastTo<[$to; 2]> for [$from; 2] {}
+      impl BitcastTo<[$to; 3]> for [$from; 3] {}
+      impl BitcastTo<[$to; 4]> for [$from; 4] {}
+    )*
+  };
+}
+impl_bitcast!(u32 => u32, i32, f32);
+impl_bitcast!(i32 => u32, i32, f32);
+impl_bitcast!(f32 => u32, i32, f32);
+
+impl<'a, DT: PrimType> SessionCodeExpr<'a, DT> {
+  /**
+   * Convert the value to `T`, as with `f32(x)`.  Floats convert to
+   * integers by truncating towards zero, and values convert to
+   * `bool` by comparing with zero.
+   */
+  pub fn cast<T>(self) -> SessionCodeExpr<'a, T>
+    where DT: ConvertibleTo<T>,
+          T: PrimType,
+  {
+    let expr = DeclareCodeExpr::new_convert(
+      T::PRIM_INFO,
+      Box::new(self.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<T>(), expr)
+  }
+
+  /** Reinterpret the bits of the value as `T`, `bitcast<T>(x)`. */
+  pub fn bitcast<T>(self) -> SessionCodeExpr<'a, T>
+    where DT: BitcastTo<T>,
+          T: PrimType,
+  {
+    let expr = DeclareCodeExpr::new_bitcast(
+      T::PRIM_INFO,
+      Box::new(self.declaration()),
+    );
+    SessionCodeExpr::new(data_type_info_for::<T>(), expr)
+  }
+}
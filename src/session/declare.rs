@@ -1,6 +1,7 @@
 
 use crate::{
   Pewter,
+  PewterError,
   types::{intern_name, SharedName},
 };
 
@@ -19,24 +20,24 @@ impl<'a> DeclareSession<'a> {
   }
 
   pub fn shader_file<'b, Func>(&'b mut self, name: &str, func: Func)
-    -> SharedName
+    -> Result<SharedName, PewterError>
     where Func: FnOnce (&mut ShaderFileSession<'b>)
+                  -> Result<(), PewterError>
   {
     let name = intern_name(name);
     let decl_file = {
       let mut func_sess = ShaderFileSession::new(name.clone());
-      func(&mut func_sess);
+      func(&mut func_sess) ?;
       func_sess.finish()
     };
-    self.pewter.register_shader_file(decl_file);
-    name
+    self.pewter.register_shader_file(decl_file) ?;
+    Ok(name)
   }
 
-  pub fn shader_file_text(&self, name: &SharedName) -> String {
+  pub fn shader_file_text(&self, name: &SharedName)
+    -> Result<String, PewterError>
+  {
     self.pewter.shader_file_text(name)
-      .expect(&format!(
-        "Failed to generate shader file text for {:?}",
-        name
-      ))
+      .ok_or_else(|| PewterError::UnknownShaderFile(name.clone()))
   }
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+use std::marker::PhantomData;
+use crate::{
+  types::{DataType, SharedName},
+  shader::ShaderFunction,
+};
+
+
+/**
+ * A handle to a function declared in a shader file, used to call
+ * it from later functions in the same file.
+ */
+pub struct SessionFunction<'a, Ret: DataType> {
+  // The name of the function.
+  name: SharedName,
+
+  // The argument and result types of the function.
+  signature: ShaderFunction,
+
+  _dummy: PhantomData<&'a Ret>,
+}
+impl<'a, Ret: DataType> SessionFunction<'a, Ret> {
+  pub(crate) fn new(name: SharedName, signature: ShaderFunction) -> Self {
+    SessionFunction { name, signature, _dummy: PhantomData }
+  }
+
+  pub(crate) fn signature(&self) -> &ShaderFunction { &self.signature }
+
+  pub fn name(&self) -> &SharedName { &self.name }
+}
+impl<'a, Ret: DataType> Clone for SessionFunction<'a, Ret> {
+  fn clone(&self) -> Self {
+    SessionFunction {
+      name: self.name.clone(),
+      signature: self.signature.clone(),
+      _dummy: PhantomData,
+    }
+  }
+}
@@ -4,16 +4,53 @@ pub mod construct;
 pub mod declare;
 pub mod shader_file;
 pub mod shader_function;
+pub mod function;
 pub mod code_expr;
+pub mod code_ops;
+pub mod builtins;
+pub mod convert;
+pub mod vector;
+pub mod var;
 
 pub use self::{
-  buffer::SessionBuffer,
-  construct::ConstructSession,
+  buffer::{SessionBuffer, SessionTailBuffer},
+  construct::{ConstructSession, ConstructDispatch},
   declare::DeclareSession,
   shader_file::ShaderFileSession,
   shader_function::{
     ShaderFunctionSession,
-    ShaderEntryFunctionSession
+    ShaderEntryFunctionSession,
+    SessionIf,
+    SessionScope,
+  },
+  function::SessionFunction,
+  code_expr::{SessionCodeExpr, SessionCodeExprTuple, lit},
+  var::SessionVar,
+  code_ops::{
+    ArithmeticWith,
+    AddWith,
+    MultiplyWith,
+    Negatable,
+    Bitwise,
+    ShiftableBy,
+    Comparable,
+    Ordered,
+    Logical,
+    LoopIndex,
+  },
+  builtins::{
+    Float,
+    FloatVector,
+    NumericVector,
+    SquareMatrix,
+    select,
+  },
+  convert::{ConvertibleTo, BitcastTo},
+  vector::{
+    Component,
+    ComponentOf,
+    Swizzle,
+    X, Y, Z, W,
+    vec2, vec3, vec4,
   },
-  code_expr::SessionCodeExpr,
 };
\ No newline at end of file
@@ -2,15 +2,25 @@
 use std::marker::PhantomData;
 
 use crate::{
+  PewterError,
   types::{
     DataType,
     data_type_info_for,
     intern_name, SharedName
   },
+  buf::{
+    AccessMode,
+    BufferAccess,
+    StorageAccess,
+    ReadAccess,
+    ReadWriteAccess,
+    UniformAccess,
+  },
   declare::{DeclareShaderFile, DeclareBuffer},
   session::{
     SessionBuffer,
-    DeclareSession,
+    SessionTailBuffer,
+    SessionFunction,
     ShaderFunctionSession,
     ShaderEntryFunctionSession,
   },
@@ -26,59 +36,222 @@ impl<'a> ShaderFileSession<'a> {
     let declare = DeclareShaderFile::new(name);
     ShaderFileSession { declare, _dummy: PhantomData }
   }
-  pub fn use_buf<DT>(&mut self, name: &str) -> SessionBuffer<'a, DT>
+  /** Use a read-write 1d storage buffer. */
+  pub fn use_buf<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 1)
+  }
+
+  /** Use a read-write 1d storage buffer. */
+  pub fn use_buf_rw<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT, ReadWriteAccess>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 1)
+  }
+
+  /** Use a read-only 1d storage buffer. */
+  pub fn use_buf_read<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT, ReadAccess>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 1)
+  }
+
+  /** Use a read-write 2d storage buffer. */
+  pub fn use_buf_2d<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 2)
+  }
+
+  /** Use a read-only 2d storage buffer. */
+  pub fn use_buf_read_2d<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT, ReadAccess>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 2)
+  }
+
+  /** Use a read-write 3d storage buffer. */
+  pub fn use_buf_3d<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT>, PewterError>
+   where DT: DataType
+  {
+    self.use_shaped_buf(name, 3)
+  }
+
+  /** Use a read-only 3d storage buffer. */
+  pub fn use_buf_read_3d<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT, ReadAccess>, PewterError>
    where DT: DataType
+  {
+    self.use_shaped_buf(name, 3)
+  }
+
+  /**
+   * Use a uniform buffer holding a single value, whose type
+   * must follow the layout rules of the uniform address space.
+   */
+  pub fn use_uniform<DT>(&mut self, name: &str)
+    -> Result<SessionBuffer<'a, DT, UniformAccess>, PewterError>
+   where DT: DataType
+  {
+    let data_type = data_type_info_for::<DT>();
+    if let Some(field) = data_type.uniform_layout_violation() {
+      return Err(PewterError::UniformLayout {
+        buffer: intern_name(name),
+        data_type: data_type.name().to_owned(),
+        field,
+      });
+    }
+    self.use_shaped_buf(name, 1)
+  }
+
+  /**
+   * Use a read-write storage buffer holding a header of type `H`
+   * followed by a runtime-sized array of `DT`.
+   */
+  pub fn use_tail_buf<H, DT>(&mut self, name: &str)
+    -> Result<SessionTailBuffer<'a, H, DT, ReadWriteAccess>, PewterError>
+   where H: DataType,
+         DT: DataType,
+  {
+    self.use_storage_tail_buf(name)
+  }
+
+  /**
+   * Use a read-only storage buffer holding a header of type `H`
+   * followed by a runtime-sized array of `DT`.
+   */
+  pub fn use_tail_buf_read<H, DT>(&mut self, name: &str)
+    -> Result<SessionTailBuffer<'a, H, DT, ReadAccess>, PewterError>
+   where H: DataType,
+         DT: DataType,
+  {
+    self.use_storage_tail_buf(name)
+  }
+
+  fn use_storage_tail_buf<H, DT, A>(&mut self, name: &str)
+    -> Result<SessionTailBuffer<'a, H, DT, A>, PewterError>
+   where H: DataType,
+         DT: DataType,
+         A: StorageAccess,
+  {
+    let name = intern_name(name);
+    let header_type = data_type_info_for::<H>();
+    let data_type = data_type_info_for::<DT>();
+    for dt in [&header_type, &data_type] {
+      if !dt.is_host_shareable() {
+        return Err(PewterError::NonHostShareableBuffer {
+          buffer: name,
+          data_type: dt.name().to_owned(),
+        });
+      }
+      if dt.has_atomics() && A::ACCESS != BufferAccess::ReadWrite {
+        return Err(PewterError::AtomicBufferAccess {
+          buffer: name,
+          data_type: dt.name().to_owned(),
+        });
+      }
+    }
+    let decl_buffer = DeclareBuffer::new_tail(
+      name.clone(),
+      header_type.clone(),
+      data_type.clone(),
+      A::ACCESS,
+    );
+    let idx = self.declare.push_buffer(decl_buffer) ?;
+    Ok(SessionTailBuffer::new(name, header_type, data_type, idx))
+  }
+
+  fn use_shaped_buf<DT, A>(&mut self, name: &str, dims: usize)
+    -> Result<SessionBuffer<'a, DT, A>, PewterError>
+   where DT: DataType,
+         A: AccessMode,
   {
     let name = intern_name(name);
     let data_type = data_type_info_for::<DT>();
-    let decl_buffer = DeclareBuffer::new(name, data_type.clone());
-    let idx = self.declare.push_buffer(decl_buffer);
-    SessionBuffer::new(data_type, idx)
+    if !data_type.is_host_shareable() {
+      return Err(PewterError::NonHostShareableBuffer {
+        buffer: name,
+        data_type: data_type.name().to_owned(),
+      });
+    }
+    if data_type.has_atomics() && A::ACCESS != BufferAccess::ReadWrite {
+      return Err(PewterError::AtomicBufferAccess {
+        buffer: name,
+        data_type: data_type.name().to_owned(),
+      });
+    }
+    let decl_buffer =
+      DeclareBuffer::new(name.clone(), data_type.clone(), A::ACCESS, dims);
+    let idx = self.declare.push_buffer(decl_buffer) ?;
+    Ok(SessionBuffer::new(name, data_type, dims, idx))
   }
 
+  /**
+   * Declare a function returning `Ret`, whose arguments and body are
+   * given by `func`.  The returned handle calls it from functions
+   * declared after it in this file.  The handle borrows the declare
+   * session along with this file, so it can't reach other files.
+   */
   pub fn function<Ret, Func>(&mut self, name: &str, func: Func)
+    -> Result<SessionFunction<'a, Ret>, PewterError>
     where Ret: DataType,
           Func: for <'x> FnOnce (&mut ShaderFunctionSession<'x, Ret>)
+                  -> Result<(), PewterError>
   {
     let name = intern_name(name);
     let decl_func = {
       let mut func_sess = ShaderFunctionSession::new(name);
-      func(&mut func_sess);
+      func(&mut func_sess) ?;
       func_sess.finish()
     };
-    self.declare.push_function(decl_func, /* is_entry = */ false);
+    let signature = decl_func.signature()
+      .expect("Non-entry function without a signature");
+    let name = decl_func.name().clone();
+    self.declare.push_function(decl_func, /* is_entry = */ false) ?;
+    Ok(SessionFunction::new(name, signature))
   }
 
   pub fn entry_function_1d<Func>(&mut self,
     name: &str,
     func: Func
-  ) where Func: for <'x> FnOnce (
+  ) -> Result<(), PewterError>
+    where Func: for <'x> FnOnce (
             &mut ShaderEntryFunctionSession<'x, u32>
-          )
+          ) -> Result<(), PewterError>
   {
     let name = intern_name(name);
     let decl_func = {
-      let mut sess = ShaderEntryFunctionSession::new(name);
-      func(&mut sess);
+      let mut sess = ShaderEntryFunctionSession::new(name) ?;
+      func(&mut sess) ?;
       sess.finish()
     };
-    self.declare.push_function(decl_func, /* is_entry = */ false);
+    self.declare.push_function(decl_func, /* is_entry = */ true) ?;
+    Ok(())
   }
 
-  pub fn entry_function_2d<Ret, Func>(&mut self,
+  pub fn entry_function_2d<Func>(&mut self,
     name: &str,
     func: Func
-  ) where Func: for <'x> FnOnce (
+  ) -> Result<(), PewterError>
+    where Func: for <'x> FnOnce (
             &mut ShaderEntryFunctionSession<'x, [u32; 2]>
-          )
+          ) -> Result<(), PewterError>
   {
     let name = intern_name(name);
     let decl_func = {
-      let mut sess = ShaderEntryFunctionSession::new(name);
-      func(&mut sess);
+      let mut sess = ShaderEntryFunctionSession::new(name) ?;
+      func(&mut sess) ?;
       sess.finish()
     };
-    self.declare.push_function(decl_func, /* is_entry = */ false);
+    self.declare.push_function(decl_func, /* is_entry = */ true) ?;
+    Ok(())
   }
 
   pub(crate) fn finish(self) -> DeclareShaderFile { self.declare }
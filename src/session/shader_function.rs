@@ -1,31 +1,410 @@
 use std::{
+  mem,
   marker::PhantomData,
+  ops::DerefMut,
 };
 use crate::{
+  PewterError,
   types::{
     DataType,
+    DataTypeInfo,
+    DataTypeTuple,
+    NonAtomic,
+    Atomic,
+    AtomicScalar,
     data_type_info_for,
-    SharedName, DataTypeInfo, intern_name, PrimType
+    SharedName, intern_name, PrimType,
   },
   declare::{
     DeclareShaderFunction,
+    DeclareCodeBody,
     DeclareCodeExpr,
-    DeclareCodeStatement
+    DeclareCodeStatement,
+    DeclareBinaryOp,
+    DeclareUnaryOp,
+  },
+  buf::WritableAccess,
+  session::{
+    SessionCodeExpr,
+    SessionCodeExprTuple,
+    SessionBuffer,
+    SessionTailBuffer,
+    code_expr::CallArgs,
+    SessionFunction,
+    lit,
+    SessionVar,
+    LoopIndex,
   },
-  session::SessionCodeExpr,
 };
 
 
 pub struct BaseShaderFunctionSession<'a> {
   declare: DeclareShaderFunction,
+
+  // The bodies of the control flow statements being built, innermost
+  // last.  Statements are added to the innermost body.
+  nested: Vec<DeclareCodeBody>,
+
+  // The number of loops enclosing the innermost body.
+  loop_depth: usize,
+
   _dummy: PhantomData<&'a ()>,
 }
 impl<'a> BaseShaderFunctionSession<'a> {
+  fn new(declare: DeclareShaderFunction) -> Self {
+    BaseShaderFunctionSession {
+      declare,
+      nested: Vec::new(),
+      loop_depth: 0,
+      _dummy: PhantomData,
+    }
+  }
+
+  /** The innermost body statements are added to. */
+  fn current_body(&mut self) -> &mut DeclareCodeBody {
+    match self.nested.last_mut() {
+      Some(body) => body,
+      None => self.declare.body(),
+    }
+  }
+
+  fn add_statement(&mut self, stmt: DeclareCodeStatement) {
+    self.current_body().add_statement(stmt);
+  }
+
+  /**
+   * Call a function declared earlier in the same shader file with
+   * `args`, a tuple of expressions matching its arguments.
+   */
+  pub fn call<Ret, Args>(&self,
+    function: &SessionFunction<'_, Ret>,
+    args: Args,
+  ) -> Result<SessionCodeExpr<'a, Ret>, PewterError>
+    where Ret: DataType,
+          Args: SessionCodeExprTuple<'a>,
+  {
+    let signature = function.signature();
+    let arg_types = <Args::Types as DataTypeTuple>::info_vec();
+    if arg_types.as_slice() != signature.arguments() {
+      let type_names = |types: &[DataTypeInfo]| {
+        types.iter().map(|dt| dt.name()).collect::<Vec<_>>().join(", ")
+      };
+      return Err(PewterError::CallArgumentMismatch {
+        function: function.name().clone(),
+        expected: type_names(signature.arguments()),
+        actual: type_names(&arg_types),
+      });
+    }
+    let expr = DeclareCodeExpr::new_call(
+      function.name().clone(),
+      CallArgs::collect(args),
+    );
+    Ok(SessionCodeExpr::new(signature.result().clone(), expr))
+  }
+
+  /** A constant value, as with the free function `lit`. */
+  pub fn lit<DT: NonAtomic>(&self, value: DT) -> SessionCodeExpr<'a, DT> {
+    lit(value)
+  }
+
+  /**
+   * Bind the value of `expr` to a name, so it is computed once
+   * however many times the returned expression is used.  The name
+   * is made unique within the function if needed.
+   */
+  pub fn let_<DT>(&mut self, name: &str, expr: SessionCodeExpr<'a, DT>)
+    -> SessionCodeExpr<'a, DT>
+    where DT: DataType
+  {
+    self.bind_let(name, expr.declaration())
+  }
+
+  fn bind_let<DT>(&mut self, name: &str, value: DeclareCodeExpr)
+    -> SessionCodeExpr<'a, DT>
+    where DT: DataType
+  {
+    let name = self.declare.add_local(name);
+    let data_type = data_type_info_for::<DT>();
+    let stmt = DeclareCodeStatement::new_let(
+      name.clone(),
+      data_type.clone(),
+      value,
+    );
+    self.add_statement(stmt);
+    SessionCodeExpr::new(data_type, DeclareCodeExpr::new_variable(name))
+  }
+
+  /**
+   * Declare a mutable variable initialized to `init`.  The name is
+   * made unique within the function if needed.
+   */
+  pub fn var<DT>(&mut self, name: &str, init: SessionCodeExpr<'a, DT>)
+    -> SessionVar<'a, DT>
+    where DT: DataType
+  {
+    let name = self.declare.add_local(name);
+    let data_type = data_type_info_for::<DT>();
+    let stmt = DeclareCodeStatement::new_var(
+      name.clone(),
+      data_type.clone(),
+      init.declaration(),
+    );
+    self.add_statement(stmt);
+    SessionVar::new(name, data_type)
+  }
+
+  /** Assign `value` to a variable. */
+  pub fn assign<DT>(&mut self,
+    var: &SessionVar<'a, DT>,
+    value: SessionCodeExpr<'a, DT>,
+  ) where DT: DataType
+  {
+    self.compound_assign(var, None, value);
+  }
+
+  fn compound_assign<DT, R>(&mut self,
+    var: &SessionVar<'a, DT>,
+    op: Option<DeclareBinaryOp>,
+    value: SessionCodeExpr<'a, R>,
+  ) where DT: DataType,
+          R: DataType,
+  {
+    let stmt = DeclareCodeStatement::new_assign(
+      var.name().clone(),
+      op,
+      value.declaration(),
+    );
+    self.add_statement(stmt);
+  }
+
+  /** Leave the innermost enclosing loop. */
+  pub fn break_(&mut self) -> Result<(), PewterError> {
+    if self.loop_depth == 0 {
+      return Err(PewterError::BreakOutsideLoop {
+        function: self.declare.name().clone(),
+      });
+    }
+    self.add_statement(DeclareCodeStatement::Break);
+    Ok(())
+  }
+
+  /** Skip to the next iteration of the innermost enclosing loop. */
+  pub fn continue_(&mut self) -> Result<(), PewterError> {
+    if self.loop_depth == 0 {
+      return Err(PewterError::ContinueOutsideLoop {
+        function: self.declare.name().clone(),
+      });
+    }
+    self.add_statement(DeclareCodeStatement::Continue);
+    Ok(())
+  }
+
+  /**
+   * Store `value` at the linear index `idx` of a buffer the shader
+   * can write to.
+   */
+  pub fn store<DT, A>(&mut self,
+    buffer: &SessionBuffer<'_, DT, A>,
+    idx: SessionCodeExpr<'a, u32>,
+    value: SessionCodeExpr<'a, DT>,
+  ) where DT: DataType,
+          A: WritableAccess,
+  {
+    let stmt = DeclareCodeStatement::new_store(
+      buffer.name().clone(),
+      idx.declaration(),
+      value.declaration(),
+    );
+    self.add_statement(stmt);
+  }
+
+  /** Store `value` as the header of a tail buffer. */
+  pub fn store_header<H, DT, A>(&mut self,
+    buffer: &SessionTailBuffer<'_, H, DT, A>,
+    value: SessionCodeExpr<'a, H>,
+  ) where H: DataType,
+          DT: DataType,
+          A: WritableAccess,
+  {
+    let stmt = DeclareCodeStatement::new_assign(
+      buffer.header_path(),
+      None,
+      value.declaration(),
+    );
+    self.add_statement(stmt);
+  }
+
+  /** Store `value` at `idx` in the array of a tail buffer. */
+  pub fn store_tail<H, DT, A>(&mut self,
+    buffer: &SessionTailBuffer<'_, H, DT, A>,
+    idx: SessionCodeExpr<'a, u32>,
+    value: SessionCodeExpr<'a, DT>,
+  ) where H: DataType,
+          DT: DataType,
+          A: WritableAccess,
+  {
+    let stmt = DeclareCodeStatement::new_store(
+      buffer.items_path(),
+      idx.declaration(),
+      value.declaration(),
+    );
+    self.add_statement(stmt);
+  }
+
+  /**
+   * Atomically load the element at `idx` of a buffer of atomics.
+   * Like the other atomic operations, the value is bound to a name
+   * where the operation is made.
+   */
+  pub fn atomic_load<S, A>(&mut self,
+    buffer: &SessionBuffer<'_, Atomic<S>, A>,
+    idx: SessionCodeExpr<'a, u32>,
+  ) -> SessionCodeExpr<'a, S>
+    where S: AtomicScalar,
+          A: WritableAccess,
+  {
+    let call = DeclareCodeExpr::new_builtin("atomicLoad", vec![
+      atomic_pointer(buffer, idx),
+    ]);
+    self.bind_let("x_atomic", call)
+  }
+
+  /** Atomically store `value` at `idx` in a buffer of atomics. */
+  pub fn atomic_store<S, A>(&mut self,
+    buffer: &SessionBuffer<'_, Atomic<S>, A>,
+    idx: SessionCodeExpr<'a, u32>,
+    value: SessionCodeExpr<'a, S>,
+  ) where S: AtomicScalar,
+          A: WritableAccess,
+  {
+    let call = DeclareCodeExpr::new_builtin("atomicStore", vec![
+      atomic_pointer(buffer, idx),
+      value.declaration(),
+    ]);
+    self.add_statement(DeclareCodeStatement::new_call(call));
+  }
+
+  /**
+   * Move the state of the session into a session with another
+   * lifetime, leaving an empty function in its place until it is
+   * restored.
+   */
+  fn take<'b>(&mut self) -> BaseShaderFunctionSession<'b> {
+    let name = self.declare.name().clone();
+    let empty = DeclareShaderFunction::new(name, false, None);
+    BaseShaderFunctionSession {
+      declare: mem::replace(&mut self.declare, empty),
+      nested: mem::take(&mut self.nested),
+      loop_depth: self.loop_depth,
+      _dummy: PhantomData,
+    }
+  }
+
+  /** Move back the state taken from the session. */
+  fn restore(&mut self, taken: BaseShaderFunctionSession<'_>) {
+    self.declare = taken.declare;
+    self.nested = taken.nested;
+    self.loop_depth = taken.loop_depth;
+  }
+
   pub(crate) fn finish(self) -> DeclareShaderFunction {
     self.declare
   }
 }
 
+/* A pointer to the element at `idx` of a buffer, `&buf[idx]`. */
+fn atomic_pointer<S, A>(
+  buffer: &SessionBuffer<'_, Atomic<S>, A>,
+  idx: SessionCodeExpr<'_, u32>,
+) -> DeclareCodeExpr
+  where S: AtomicScalar,
+        A: WritableAccess,
+{
+  let element = DeclareCodeExpr::new_index(
+    buffer.name().clone(),
+    Box::new(idx.declaration()),
+  );
+  DeclareCodeExpr::new_unary(DeclareUnaryOp::AddressOf, Box::new(element))
+}
+
+/**
+ * Atomic read-modify-write operations, which apply `op` to the
+ * element at `idx` of a buffer of atomics and `value`, and return
+ * the value the element held before.  There is no compare-exchange,
+ * as naga 0.10 cannot validate `atomicCompareExchangeWeak`.
+ */
+macro_rules! impl_atomic_rmw {
+  ($($doc:literal $method:ident => $builtin:literal;)*) => {
+    impl<'a> BaseShaderFunctionSession<'a> {
+      $(
+        #[doc = $doc]
+        pub fn $method<S, A>(&mut self,
+          buffer: &SessionBuffer<'_, Atomic<S>, A>,
+          idx: SessionCodeExpr<'a, u32>,
+          value: SessionCodeExpr<'a, S>,
+        ) -> SessionCodeExpr<'a, S>
+          where S: AtomicScalar,
+                A: WritableAccess,
+        {
+          let call = DeclareCodeExpr::new_builtin($builtin, vec![
+            atomic_pointer(buffer, idx),
+            value.declaration(),
+          ]);
+          self.bind_let("x_atomic", call)
+        }
+      )*
+    }
+  };
+}
+impl_atomic_rmw!(
+  "`atomicAdd(&buf[idx], value)`" atomic_add => "atomicAdd";
+  "`atomicSub(&buf[idx], value)`" atomic_sub => "atomicSub";
+  "`atomicMax(&buf[idx], value)`" atomic_max => "atomicMax";
+  "`atomicMin(&buf[idx], value)`" atomic_min => "atomicMin";
+  "`atomicAnd(&buf[idx], value)`" atomic_and => "atomicAnd";
+  "`atomicOr(&buf[idx], value)`" atomic_or => "atomicOr";
+  "`atomicXor(&buf[idx], value)`" atomic_xor => "atomicXor";
+  "`atomicExchange(&buf[idx], value)`" atomic_exchange => "atomicExchange";
+);
+
+/**
+ * Compound assignments, as `var op= value`.  Each is allowed where
+ * `var = var op value` would be.
+ */
+macro_rules! impl_compound_assign {
+  ($($doc:literal $method:ident => $trait:ident, $op:ident;)*) => {
+    impl<'a> BaseShaderFunctionSession<'a> {
+      $(
+        #[doc = $doc]
+        pub fn $method<DT, R>(&mut self,
+          var: &SessionVar<'a, DT>,
+          value: SessionCodeExpr<'a, R>,
+        ) where DT: DataType,
+                R: DataType,
+                SessionCodeExpr<'a, DT>: std::ops::$trait<
+                  SessionCodeExpr<'a, R>,
+                  Output = SessionCodeExpr<'a, DT>
+                >,
+        {
+          self.compound_assign(var, Some(DeclareBinaryOp::$op), value);
+        }
+      )*
+    }
+  };
+}
+impl_compound_assign!(
+  "`var += value`" add_assign => Add, Add;
+  "`var -= value`" sub_assign => Sub, Sub;
+  "`var *= value`" mul_assign => Mul, Mul;
+  "`var /= value`" div_assign => Div, Div;
+  "`var %= value`" rem_assign => Rem, Rem;
+  "`var &= value`" and_assign => BitAnd, BitAnd;
+  "`var |= value`" or_assign => BitOr, BitOr;
+  "`var ^= value`" xor_assign => BitXor, BitXor;
+  "`var <<= value`" shl_assign => Shl, Shl;
+  "`var >>= value`" shr_assign => Shr, Shr;
+);
+
 pub struct ShaderFunctionSession<'a, Ret: DataType> {
   base: BaseShaderFunctionSession<'a>,
   _dummy: PhantomData<&'a Ret>,
@@ -49,28 +428,28 @@ impl<'a, Ret: DataType> ShaderFunctionSession<'a, Ret> {
   pub(crate) fn new(name: SharedName) -> Self {
     let ret_dt = data_type_info_for::<Ret>();
     let declare = DeclareShaderFunction::new(name, false, Some(ret_dt));
-    let base = BaseShaderFunctionSession { declare, _dummy: PhantomData };
+    let base = BaseShaderFunctionSession::new(declare);
     ShaderFunctionSession { base, _dummy: PhantomData }
   }
 
-  pub fn use_arg<DT>(&mut self, name: &str) -> SessionCodeExpr<'a, DT>
-   where DT: DataType
+  pub fn use_arg<DT>(&mut self, name: &str)
+    -> Result<SessionCodeExpr<'a, DT>, PewterError>
+   where DT: NonAtomic
   {
     let name = intern_name(name);
     let data_type = data_type_info_for::<DT>();
-    let arg_idx =
-      self.base.declare.push_argument(&name, data_type.clone());
+    self.base.declare.push_argument(&name, data_type.clone()) ?;
     let arg = DeclareCodeExpr::new_argument(name);
-    SessionCodeExpr::new(data_type, arg)
+    Ok(SessionCodeExpr::new(data_type, arg))
   }
 
   pub fn return_stmt(&mut self, expr: SessionCodeExpr<'a, Ret>) {
     let stmt = DeclareCodeStatement::new_return(expr.declaration());
-    self.base.declare.body().add_statement(stmt);
+    self.base.add_statement(stmt);
   }
 
   pub(crate) fn finish(self) -> DeclareShaderFunction {
-    self.base.declare
+    self.base.finish()
   }
 }
 
@@ -98,27 +477,215 @@ impl<'a, Coord> std::ops::DerefMut
 impl<'a, Coord> ShaderEntryFunctionSession<'a, Coord>
   where Coord: PrimType,
 {
-  pub(crate) fn new(name: SharedName) -> Self {
+  pub(crate) fn new(name: SharedName) -> Result<Self, PewterError> {
     let data_type = data_type_info_for::<Coord>();
     let coord = intern_name("global_id");
-    let mut declare = DeclareShaderFunction::new(name, false, None);
-    declare.push_argument(&coord, data_type);
-    let base = BaseShaderFunctionSession {
-      declare, _dummy: PhantomData
-    };
-    ShaderEntryFunctionSession { base, _dummy: PhantomData }
+    let mut declare = DeclareShaderFunction::new(name, true, None);
+    declare.push_argument(&coord, data_type.clone()) ?;
+    if declare.entry_dims().is_none() {
+      return Err(PewterError::UnsupportedEntryCoordinate {
+        function: declare.name().clone(),
+        data_type: data_type.name().to_owned(),
+      });
+    }
+    let base = BaseShaderFunctionSession::new(declare);
+    Ok(ShaderEntryFunctionSession { base, _dummy: PhantomData })
   }
 
-  pub fn get_arg(&mut self) -> SessionCodeExpr<'a, u32> {
+  pub fn get_arg(&mut self) -> SessionCodeExpr<'a, Coord> {
     let data_type = self.declare.argument_type(0).clone();
     let name = self.declare.argument_name(0).clone();
-    debug_assert!(data_type == data_type_info_for::<u32>());
 
     let arg = DeclareCodeExpr::new_argument(name);
     SessionCodeExpr::new(data_type, arg)
   }
 
   pub(crate) fn finish(self) -> DeclareShaderFunction {
-    self.base.declare
+    self.base.finish()
+  }
+}
+/**
+ * Build a nested body with `func`, which is given the session so
+ * statements are added with its usual methods.
+ */
+fn nest_body<'a, S, Func>(sess: &mut S, is_loop: bool, func: Func)
+  -> Result<DeclareCodeBody, PewterError>
+  where S: DerefMut<Target = BaseShaderFunctionSession<'a>>,
+        Func: FnOnce (&mut S) -> Result<(), PewterError>
+{
+  sess.nested.push(DeclareCodeBody::new());
+  if is_loop { sess.loop_depth += 1; }
+  let result = func(sess);
+  if is_loop { sess.loop_depth -= 1; }
+  let body = sess.nested.pop().expect("Nested body missing");
+  result.map(|()| body)
+}
+
+/**
+ * The session given to the closure building a nested body, with the
+ * body's own lifetime `'b`.  Expressions and variables of the
+ * enclosing bodies can be used in the nested body, but those made
+ * in it can't be used once it is closed:
+ *
+ * ```compile_fail
+ * # use pewter::{Pewter, PewterError, lit};
+ * # fn declare(pewter: &Pewter) -> Result<(), PewterError> {
+ * pewter.declare(|decl| {
+ *   decl.shader_file("scoped", |decl_sh| {
+ *     decl_sh.entry_function_1d("scoped_entry", |decl_sf| {
+ *       let mut inner = None;
+ *       decl_sf.if_(lit(true), |body| {
+ *         inner = Some(body.let_("x", lit(1_u32)));
+ *         Ok(())
+ *       }) ?;
+ *       decl_sf.let_("y", inner.unwrap());
+ *       Ok(())
+ *     })
+ *   }) ?;
+ *   Ok(())
+ * })
+ * # }
+ * ```
+ */
+pub struct SessionScope<'b, 'a: 'b, S> {
+  sess: S,
+  _dummy: PhantomData<(&'b (), &'a ())>,
+}
+impl<'b, 'a, S> std::ops::Deref for SessionScope<'b, 'a, S> {
+  type Target = S;
+  fn deref(&self) -> &S {
+    &self.sess
+  }
+}
+impl<'b, 'a, S> std::ops::DerefMut for SessionScope<'b, 'a, S> {
+  fn deref_mut(&mut self) -> &mut S {
+    &mut self.sess
   }
-}
\ No newline at end of file
+}
+
+/**
+ * An if statement just added to a function, which can be given an
+ * else body.
+ */
+pub struct SessionIf<'s, S> {
+  sess: &'s mut S,
+}
+
+/**
+ * The control flow statements, which are shared by both kinds of
+ * function session.
+ */
+macro_rules! impl_control_flow {
+  ($session:ident, $param:ident : $bound:ident) => {
+    impl<'a, $param: $bound> $session<'a, $param> {
+      /**
+       * Run `func` on a session with the shorter lifetime `'b` of a
+       * nested body.  The state of the session is moved into the
+       * nested session, and moved back when `func` returns.
+       */
+      fn with_scope<Func>(&mut self, func: Func) -> Result<(), PewterError>
+        where Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>
+              ) -> Result<(), PewterError>
+      {
+        let mut scope = SessionScope {
+          sess: $session { base: self.base.take(), _dummy: PhantomData },
+          _dummy: PhantomData,
+        };
+        let result = func(&mut scope);
+        self.base.restore(scope.sess.base);
+        result
+      }
+
+      /** Build the body run when `cond` is true. */
+      pub fn if_<Func>(&mut self,
+        cond: SessionCodeExpr<'a, bool>,
+        func: Func
+      ) -> Result<SessionIf<'_, Self>, PewterError>
+        where Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>
+              ) -> Result<(), PewterError>
+      {
+        let then = nest_body(self, false, |sess| sess.with_scope(func)) ?;
+        let stmt = DeclareCodeStatement::new_if(cond.declaration(), then);
+        self.base.add_statement(stmt);
+        Ok(SessionIf { sess: self })
+      }
+
+      /** Build the body of a loop, which runs until it breaks. */
+      pub fn loop_<Func>(&mut self, func: Func) -> Result<(), PewterError>
+        where Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>
+              ) -> Result<(), PewterError>
+      {
+        let body = nest_body(self, true, |sess| sess.with_scope(func)) ?;
+        self.base.add_statement(DeclareCodeStatement::new_loop(body));
+        Ok(())
+      }
+
+      /** Build the body of a loop, which runs while `cond` is true. */
+      pub fn while_<Func>(&mut self,
+        cond: SessionCodeExpr<'a, bool>,
+        func: Func
+      ) -> Result<(), PewterError>
+        where Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>
+              ) -> Result<(), PewterError>
+      {
+        let body = nest_body(self, true, |sess| sess.with_scope(func)) ?;
+        let stmt = DeclareCodeStatement::new_while(cond.declaration(), body);
+        self.base.add_statement(stmt);
+        Ok(())
+      }
+
+      /**
+       * Build the body of a loop, which runs once for each value
+       * counting up from `lo` to just below `hi`.  The body is given
+       * the current value.
+       */
+      pub fn for_range<DT, Func>(&mut self,
+        lo: SessionCodeExpr<'a, DT>,
+        hi: SessionCodeExpr<'a, DT>,
+        func: Func
+      ) -> Result<(), PewterError>
+        where DT: LoopIndex,
+              Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>,
+                SessionCodeExpr<'b, DT>,
+              ) -> Result<(), PewterError>
+      {
+        let var = self.declare.add_local("x_counter");
+        let data_type = data_type_info_for::<DT>();
+        let counter = DeclareCodeExpr::new_variable(var.clone());
+        let body = nest_body(self, true, |sess| {
+          sess.with_scope(|scope| {
+            func(scope, SessionCodeExpr::new(data_type.clone(), counter))
+          })
+        }) ?;
+        let stmt = DeclareCodeStatement::new_for_range(var, data_type,
+          lo.declaration(), hi.declaration(), body);
+        self.base.add_statement(stmt);
+        Ok(())
+      }
+    }
+
+    impl<'s, 'a, $param: $bound> SessionIf<'s, $session<'a, $param>> {
+      /** Build the body run when the condition is false. */
+      pub fn else_<Func>(self, func: Func) -> Result<(), PewterError>
+        where Func: for <'b> FnOnce (
+                &mut SessionScope<'b, 'a, $session<'b, $param>>
+              ) -> Result<(), PewterError>
+      {
+        let els = nest_body(self.sess, false,
+          |sess| sess.with_scope(func)) ?;
+        let added = self.sess.current_body().last_statement_mut()
+          .map(|stmt| stmt.set_else(els))
+          .unwrap_or(false);
+        debug_assert!(added, "Else body without an if statement.");
+        Ok(())
+      }
+    }
+  };
+}
+impl_control_flow!(ShaderFunctionSession, Ret: DataType);
+impl_control_flow!(ShaderEntryFunctionSession, Coord: PrimType);
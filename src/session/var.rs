@@ -0,0 +1,34 @@
+use std::marker::PhantomData;
+use crate::{
+  types::{
+    DataType,
+    DataTypeInfo,
+    SharedName,
+  },
+  declare::DeclareCodeExpr,
+  session::SessionCodeExpr,
+};
+
+
+/**
+ * A mutable local variable declared in a function session.
+ * It is assigned through the session, and read with `get`.
+ */
+pub struct SessionVar<'a, DT: DataType> {
+  name: SharedName,
+  data_type: DataTypeInfo,
+  _dummy: PhantomData<&'a DT>,
+}
+impl<'a, DT: DataType> SessionVar<'a, DT> {
+  pub(crate) fn new(name: SharedName, data_type: DataTypeInfo) -> Self {
+    SessionVar { name, data_type, _dummy: PhantomData }
+  }
+
+  pub(crate) fn name(&self) -> &SharedName { &self.name }
+
+  /** An expression for the current value of the variable. */
+  pub fn get(&self) -> SessionCodeExpr<'a, DT> {
+    let expr = DeclareCodeExpr::new_variable(self.name.clone());
+    SessionCodeExpr::new(self.data_type.clone(), expr)
+  }
+}
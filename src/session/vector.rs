@@ -0,0 +1,202 @@
+use crate::{
+  types::{PrimType, VectorType, VectorOf, data_type_info_for},
+  declare::DeclareCodeExpr,
+  session::SessionCodeExpr,
+};
+
+
+/** A vector component, used to pick components with `swizzle`. */
+pub trait Component {
+  const INDEX: usize;
+}
+
+/** The first component of a vector, `.x`. */
+#[derive(Clone, Copy, Debug)]
+pub struct X;
+
+/** The second component of a vector, `.y`. */
+#[derive(Clone, Copy, Debug)]
+pub struct Y;
+
+/** The third component of a vector, `.z`. */
+#[derive(Clone, Copy, Debug)]
+pub struct Z;
+
+/** The fourth component of a vector, `.w`. */
+#[derive(Clone, Copy, Debug)]
+pub struct W;
+
+impl Component for X { const INDEX: usize = 0; }
+impl Component for Y { const INDEX: usize = 1; }
+impl Component for Z { const INDEX: usize = 2; }
+impl Component for W { const INDEX: usize = 3; }
+
+/**
+ * Components which vectors of type `V` have, so that picking `Z`
+ * from a two component vector fails to compile.
+ */
+pub trait ComponentOf<V: VectorType>: Component {}
+
+// Every vector has at least two components.
+impl<V: VectorType> ComponentOf<V> for X {}
+impl<V: VectorType> ComponentOf<V> for Y {}
+
+macro_rules! impl_component_of {
+  ($($scalar:ty),*) => {
+    $(
+      impl ComponentOf<[$scalar; 3]> for Z {}
+      impl ComponentOf<[$scalar; 4]> for Z {}
+      impl ComponentOf<[$scalar; 4]> for W {}
+    )*
+  };
+}
+impl_component_of!(u32, i32, f32, bool);
+
+/**
+ * Tuples of 2 to 4 components of vectors of type `V`, picking a
+ * vector of `Output` with `swizzle`.  Components may repeat.
+ */
+pub trait Swizzle<V: VectorType> {
+  type Output: VectorType<Field = V::Field>;
+  const INDICES: &'static [usize];
+}
+
+impl<V, A, B> Swizzle<V> for (A, B)
+  where V: VectorType,
+        V::Field: VectorOf<2>,
+        A: ComponentOf<V>,
+        B: ComponentOf<V>,
+{
+  type Output = <V::Field as VectorOf<2>>::Vector;
+  const INDICES: &'static [usize] = &[A::INDEX, B::INDEX];
+}
+
+impl<V, A, B, C> Swizzle<V> for (A, B, C)
+  where V: VectorType,
+        V::Field: VectorOf<3>,
+        A: ComponentOf<V>,
+        B: ComponentOf<V>,
+        C: ComponentOf<V>,
+{
+  type Output = <V::Field as VectorOf<3>>::Vector;
+  const INDICES: &'static [usize] = &[A::INDEX, B::INDEX, C::INDEX];
+}
+
+impl<V, A, B, C, D> Swizzle<V> for (A, B, C, D)
+  where V: VectorType,
+        V::Field: VectorOf<4>,
+        A: ComponentOf<V>,
+        B: ComponentOf<V>,
+        C: ComponentOf<V>,
+        D: ComponentOf<V>,
+{
+  type Output = <V::Field as VectorOf<4>>::Vector;
+  const INDICES: &'static [usize] =
+    &[A::INDEX, B::INDEX, C::INDEX, D::INDEX];
+}
+
+impl<'a, V: VectorType> SessionCodeExpr<'a, V> {
+  /** The component `C` of the vector, such as `v.z`. */
+  pub fn component<C: ComponentOf<V>>(self, _component: C)
+    -> SessionCodeExpr<'a, V::Field>
+  {
+    swizzle(self, vec![C::INDEX])
+  }
+
+  /** `v.x` */
+  pub fn x(self) -> SessionCodeExpr<'a, V::Field> { self.component(X) }
+  /** `v.y` */
+  pub fn y(self) -> SessionCodeExpr<'a, V::Field> { self.component(Y) }
+  /** `v.z`, for vectors of 3 or 4 components. */
+  pub fn z(self) -> SessionCodeExpr<'a, V::Field>
+    where Z: ComponentOf<V>
+  {
+    self.component(Z)
+  }
+  /** `v.w`, for vectors of 4 components. */
+  pub fn w(self) -> SessionCodeExpr<'a, V::Field>
+    where W: ComponentOf<V>
+  {
+    self.component(W)
+  }
+
+  /**
+   * A vector of the components picked by `components`, such as
+   * `v.swizzle((Z, Y, X))` for `v.zyx`.
+   */
+  pub fn swizzle<S: Swizzle<V>>(self, _components: S)
+    -> SessionCodeExpr<'a, S::Output>
+  {
+    swizzle(self, S::INDICES.to_vec())
+  }
+
+  /** `v.xy` */
+  pub fn xy(self) -> SessionCodeExpr<'a, <V::Field as VectorOf<2>>::Vector>
+    where V::Field: VectorOf<2>
+  {
+    self.swizzle((X, Y))
+  }
+  /** `v.xyz`, for vectors of 3 or 4 components. */
+  pub fn xyz(self) -> SessionCodeExpr<'a, <V::Field as VectorOf<3>>::Vector>
+    where V::Field: VectorOf<3>,
+          Z: ComponentOf<V>,
+  {
+    self.swizzle((X, Y, Z))
+  }
+}
+
+/* Pick the components at `indices` from `vector`. */
+fn swizzle<'a, V: VectorType, DT: PrimType>(
+  vector: SessionCodeExpr<'a, V>,
+  indices: Vec<usize>,
+) -> SessionCodeExpr<'a, DT> {
+  let expr = DeclareCodeExpr::new_swizzle(
+    Box::new(vector.declaration()),
+    indices,
+  );
+  SessionCodeExpr::new(data_type_info_for::<DT>(), expr)
+}
+
+/* Build a vector of `S` from its components. */
+fn construct<'a, S, const N: usize>(
+  components: [SessionCodeExpr<'a, S>; N],
+) -> SessionCodeExpr<'a, S::Vector>
+  where S: VectorOf<N>
+{
+  let args = components.into_iter()
+    .map(|c| c.declaration())
+    .collect::<Vec<_>>();
+  let data_type = data_type_info_for::<S::Vector>();
+  let expr = DeclareCodeExpr::new_construct(
+    <S::Vector as PrimType>::PRIM_INFO,
+    args,
+  );
+  SessionCodeExpr::new(data_type, expr)
+}
+
+/** A two component vector, `vec2(x, y)`. */
+pub fn vec2<'a, S: VectorOf<2>>(
+  x: SessionCodeExpr<'a, S>,
+  y: SessionCodeExpr<'a, S>,
+) -> SessionCodeExpr<'a, S::Vector> {
+  construct([x, y])
+}
+
+/** A three component vector, `vec3(x, y, z)`. */
+pub fn vec3<'a, S: VectorOf<3>>(
+  x: SessionCodeExpr<'a, S>,
+  y: SessionCodeExpr<'a, S>,
+  z: SessionCodeExpr<'a, S>,
+) -> SessionCodeExpr<'a, S::Vector> {
+  construct([x, y, z])
+}
+
+/** A four component vector, `vec4(x, y, z, w)`. */
+pub fn vec4<'a, S: VectorOf<4>>(
+  x: SessionCodeExpr<'a, S>,
+  y: SessionCodeExpr<'a, S>,
+  z: SessionCodeExpr<'a, S>,
+  w: SessionCodeExpr<'a, S>,
+) -> SessionCodeExpr<'a, S::Vector> {
+  construct([x, y, z, w])
+}
@@ -5,7 +5,18 @@ use crate::{
 /**
  * A pure function in a shader language.
  */
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ShaderFunction {
   arguments: Vec<DataTypeInfo>,
   result: DataTypeInfo,
-}
\ No newline at end of file
+}
+impl ShaderFunction {
+  pub(crate) fn new(arguments: Vec<DataTypeInfo>, result: DataTypeInfo)
+    -> Self
+  {
+    ShaderFunction { arguments, result }
+  }
+
+  pub fn arguments(&self) -> &[DataTypeInfo] { &self.arguments }
+  pub fn result(&self) -> &DataTypeInfo { &self.result }
+}
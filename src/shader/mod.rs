@@ -1,8 +1,7 @@
-
 mod function;
 
 pub use self::{
   function::{
     ShaderFunction,
   },
-};
\ No newline at end of file
+};
@@ -0,0 +1,80 @@
+use crate::{ Pewter, PewterConfig, PewterError };
+
+#[test]
+fn test_buffer_access() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_access = None;
+      pewter.declare(|decl| {
+        sh_access = Some(decl.shader_file("access", |decl_sh| {
+          decl_sh.use_buf_read::<u32>("input") ?;
+          decl_sh.use_buf_rw::<u32>("output") ?;
+          let params = decl_sh.use_uniform::<[f32; 4]>("params") ?;
+          decl_sh.function::<[f32; 4], _>("get_params", |decl_sf| {
+            decl_sf.return_stmt(params.load_uniform());
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("noop_entry", |_decl_sf| Ok(()))
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_access = sh_access.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_access) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("var<storage, read> input: array<u32>;"));
+      assert!(text.contains("var<storage, read_write> output: array<u32>;"));
+      assert!(text.contains("var<uniform> params: vec4<f32>;"));
+      assert!(text.contains("return params;"));
+
+      let params = pewter.create_uniform_buffer::<[f32; 4]>("params")
+        .expect("Failed to create uniform buffer.");
+      assert!(params.is_uniform());
+      assert_eq!(params.len(), 1);
+      params.write_slice(&[[1.0, 2.0, 3.0, 4.0]])
+        .expect("Failed to write uniform buffer.");
+
+      let input = (0 .. 16_u32).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let in_buf = cons.buffer::<u32>("input", input.len()) ?;
+        let out_buf = cons.buffer::<u32>("output", input.len()) ?;
+        cons.write_buffer(&in_buf, &input) ?;
+        cons.dispatch(&sh_access, "noop_entry", &[input.len() as u32]) ?
+          .bind("input", &in_buf) ?
+          .bind("output", &out_buf) ?
+          .bind("params", &params) ?;
+        let readback = cons.read_buffer(&params);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      assert_eq!(output, vec![[1.0, 2.0, 3.0, 4.0]]);
+
+      // Storage and uniform buffers can't stand in for each other.
+      let result = pewter.construct::<(), _>(|cons| {
+        let params = cons.buffer::<[f32; 4]>("params", 1) ?;
+        cons.dispatch(&sh_access, "noop_entry", &[1]) ?
+          .bind("params", &params) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferUsageMismatch { .. })));
+      let result = pewter.construct::<(), _>(|cons| {
+        let input = cons.uniform_buffer::<u32>("input") ?;
+        cons.dispatch(&sh_access, "noop_entry", &[1]) ?
+          .bind("input", &input) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferUsageMismatch { .. })));
+    });
+}
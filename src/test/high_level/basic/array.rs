@@ -0,0 +1,123 @@
+use crate::{
+  Pewter,
+  PewterConfig,
+  DataType,
+  RecordType,
+  FixedArray,
+  lit,
+};
+
+#[test]
+fn test_fixed_arrays() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let table = FixedArray([1.0_f32, -1.0, 0.5, 2.0, 0.0, 3.0, -0.25, 1.0]);
+      let marks = FixedArray([7_u32, 11, 13, 17]);
+      let mut sh_arr = None;
+      pewter.declare(|decl| {
+        sh_arr = Some(decl.shader_file("arr", |decl_sh| {
+          let rows = decl_sh.use_buf_read::<FixedArray<f32, 8>>("rows") ?;
+          let sums = decl_sh.use_buf::<f32>("sums") ?;
+          let hist = decl_sh.use_buf::<FixedArray<u32, 4>>("hist") ?;
+          let bins = decl_sh.use_buf::<Bins>("bins") ?;
+          decl_sh.entry_function_1d("arr_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            // Variables can be indexed by a loop counter.
+            let weights = decl_sf.var("weights", lit(table));
+            let sum = decl_sf.var("sum", lit(0.0_f32));
+            decl_sf.for_range(lit(0_u32), lit(8_u32), |body, j| {
+              body.add_assign(&sum,
+                rows.load(id.clone()).index(j.clone())
+                  * weights.get().index(j));
+              Ok(())
+            }) ?;
+            decl_sf.store(&sums, id.clone(), sum.get());
+            decl_sf.if_((id.clone() & lit(1)).eq(lit(0)), |body| {
+              body.store(&hist, id.clone(), lit(marks));
+              Ok(())
+            }) ?;
+            decl_sf.store(&bins, id.clone(), bins.load(id));
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_arr = sh_arr.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_arr) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("var weights: array<f32, 8> = array<f32, 8>("));
+      assert!(text.contains(
+        "(rows[global_id][x_counter] * weights[x_counter])"));
+      assert!(text.contains(
+        "hist[global_id] = array<u32, 4>(7u, 11u, 13u, 17u);"));
+      assert!(text.contains("  counts: array<u32, 3>,\n"));
+
+      let n = 16_usize;
+      let rows_in = (0 .. n)
+        .map(|i| {
+          let mut row = FixedArray::<f32, 8>::default();
+          for (j, x) in row.iter_mut().enumerate() {
+            *x = (i * 8 + j) as f32 * 0.5;
+          }
+          row
+        })
+        .collect::<Vec<_>>();
+      let hist_in = (0 .. n as u32)
+        .map(|i| FixedArray([i, i + 1, i + 2, i + 3]))
+        .collect::<Vec<_>>();
+      let bins_in = (0 .. n as u32)
+        .map(|i| Bins { counts: FixedArray([i, i * 2, i * 3]), total: i * 6 })
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let rows = cons.buffer::<FixedArray<f32, 8>>("rows", n) ?;
+        let sums = cons.buffer::<f32>("sums", n) ?;
+        let hist = cons.buffer::<FixedArray<u32, 4>>("hist", n) ?;
+        let bins = cons.buffer::<Bins>("bins", n) ?;
+        cons.write_buffer(&rows, &rows_in) ?;
+        cons.write_buffer(&hist, &hist_in) ?;
+        cons.write_buffer(&bins, &bins_in) ?;
+        cons.dispatch(&sh_arr, "arr_entry", &[n as u32]) ?
+          .bind("rows", &rows) ?
+          .bind("sums", &sums) ?
+          .bind("hist", &hist) ?
+          .bind("bins", &bins) ?;
+        let sums_back = cons.read_buffer(&sums);
+        let hist_back = cons.read_buffer(&hist);
+        let bins_back = cons.read_buffer(&bins);
+        cons.result(move |out| {
+          (out.get(sums_back), out.get(hist_back), out.get(bins_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (sums_out, hist_out, bins_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let expected = rows_in[i].iter().zip(table.iter())
+          .map(|(x, w)| x * w)
+          .sum::<f32>();
+        assert_eq!(sums_out[i], expected);
+        let expected_hist = if i % 2 == 0 { marks } else { hist_in[i] };
+        assert_eq!(hist_out[i], expected_hist);
+        assert_eq!(bins_out[i], bins_in[i]);
+      }
+    });
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Bins {
+  counts: FixedArray<u32, 3>,
+  total: u32,
+}
@@ -0,0 +1,166 @@
+use crate::{
+  Pewter,
+  PewterConfig,
+  PewterError,
+  Atomic,
+  lit,
+};
+
+#[test]
+fn test_atomics() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_atom = None;
+      pewter.declare(|decl| {
+        sh_atom = Some(decl.shader_file("atom", |decl_sh| {
+          let hist = decl_sh.use_buf::<Atomic<u32>>("hist") ?;
+          let stats = decl_sh.use_buf::<Atomic<i32>>("stats") ?;
+          let bits = decl_sh.use_buf::<Atomic<u32>>("bits") ?;
+          let olds = decl_sh.use_buf::<u32>("olds") ?;
+          decl_sh.entry_function_1d("atom_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let signed = id.clone().cast::<i32>();
+            decl_sf.atomic_add(&hist, (id.clone() * lit(7)) % lit(5), lit(1));
+            decl_sf.atomic_sub(&stats, lit(0), lit(1));
+            decl_sf.atomic_max(&stats, lit(1), signed.clone());
+            decl_sf.atomic_min(&stats, lit(2), -signed);
+            decl_sf.atomic_store(&stats, lit(3), lit(7));
+            let bit = lit(1_u32) << (id.clone() % lit(32));
+            decl_sf.atomic_or(&bits, lit(0), bit.clone());
+            decl_sf.atomic_and(&bits, lit(1), !bit);
+            decl_sf.atomic_xor(&bits, lit(2), id.clone());
+            decl_sf.atomic_exchange(&bits, lit(3), id.clone() + lit(1));
+            let old = decl_sf.atomic_add(&bits, lit(4), lit(1));
+            decl_sf.store(&olds, id, old);
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("load_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let count = decl_sf.atomic_load(&hist, id.clone());
+            decl_sf.store(&olds, id, count);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_atom = sh_atom.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_atom) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains(
+        "var<storage, read_write> hist: array<atomic<u32>>;"));
+      assert!(text.contains(
+        "let x_atomic: u32 = atomicAdd((&hist[((global_id * 7u) % 5u)]), 1u);"
+      ));
+      assert!(text.contains("  atomicStore((&stats[3u]), 7i);\n"));
+      assert!(text.contains("olds[global_id] = x_atomic_"));
+
+      let n = 64_u32;
+      let construct = pewter.construct(|cons| {
+        let hist = cons.buffer::<Atomic<u32>>("hist", 5) ?;
+        let stats = cons.buffer::<Atomic<i32>>("stats", 4) ?;
+        let bits = cons.buffer::<Atomic<u32>>("bits", 5) ?;
+        let olds = cons.buffer::<u32>("olds", n as usize) ?;
+        cons.write_buffer(&stats, &[Atomic(0), Atomic(-1), Atomic(1)]) ?;
+        cons.write_buffer(&bits, &[Atomic(0), Atomic(!0), Atomic(0)]) ?;
+        cons.dispatch(&sh_atom, "atom_entry", &[n]) ?
+          .bind("hist", &hist) ?
+          .bind("stats", &stats) ?
+          .bind("bits", &bits) ?
+          .bind("olds", &olds) ?;
+        let olds_back = cons.read_buffer(&olds);
+        cons.dispatch(&sh_atom, "load_entry", &[5]) ?
+          .bind("hist", &hist) ?
+          .bind("stats", &stats) ?
+          .bind("bits", &bits) ?
+          .bind("olds", &olds) ?;
+        let loads_back = cons.read_buffer(&olds);
+        let hist_back = cons.read_buffer(&hist);
+        let stats_back = cons.read_buffer(&stats);
+        let bits_back = cons.read_buffer(&bits);
+        cons.result(move |out| {
+          (
+            out.get(olds_back),
+            out.get(loads_back),
+            out.get(hist_back),
+            out.get(stats_back),
+            out.get(bits_back),
+          )
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (olds_out, loads_out, hist_out, stats_out, bits_out) =
+        construct.perform().await
+          .expect("Failed to perform construct.");
+
+      let mut expected_hist = [0_u32; 5];
+      for i in 0 .. n {
+        expected_hist[((i * 7) % 5) as usize] += 1;
+      }
+      let hist_out = hist_out.iter().map(|a| a.0).collect::<Vec<_>>();
+      assert_eq!(hist_out, expected_hist);
+      assert_eq!(&loads_out[.. 5], &expected_hist);
+      assert_eq!(stats_out, vec![
+        Atomic(-(n as i32)),
+        Atomic(n as i32 - 1),
+        Atomic(-(n as i32 - 1)),
+        Atomic(7),
+      ]);
+      let xor = (0 .. n).fold(0, |acc, i| acc ^ i);
+      assert_eq!(&bits_out[.. 3], &[Atomic(!0), Atomic(0), Atomic(xor)]);
+      assert!((1 ..= n).contains(&bits_out[3].0));
+      assert_eq!(bits_out[4], Atomic(n));
+      // Each invocation saw a different count before its increment.
+      let mut olds_out = olds_out;
+      olds_out.sort();
+      assert_eq!(olds_out, (0 .. n).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn test_atomic_declarations() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      // Atomics are only allowed in read-write storage buffers.
+      let result = pewter.declare(|decl| {
+        decl.shader_file("atom_read", |decl_sh| {
+          decl_sh.use_buf_read::<Atomic<u32>>("counts") ?;
+          Ok(())
+        }) ?;
+        Ok(())
+      });
+      match result {
+        Err(PewterError::AtomicBufferAccess { buffer, data_type }) => {
+          assert_eq!(buffer.as_ref(), "counts");
+          assert_eq!(data_type, "atomic<u32>");
+        },
+        _ => panic!("Expected an atomic buffer access error"),
+      }
+      let result = pewter.declare(|decl| {
+        decl.shader_file("atom_uniform", |decl_sh| {
+          decl_sh.use_uniform::<Atomic<i32>>("count") ?;
+          Ok(())
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::AtomicBufferAccess { .. })));
+    });
+}
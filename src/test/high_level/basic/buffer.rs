@@ -0,0 +1,107 @@
+
+use crate::{
+  Pewter, PewterConfig, PewterError, BufferShape,
+  types::{
+    RecordType,
+    DataType,
+    DataTypeSpecialize,
+    DataTypeSpecializeRef,
+    DataTypeSpecializeMut,
+    RecordTypeFieldSpecifier,
+    record_info_for_type,
+  },
+};
+
+#[test]
+fn test_persistent_buffer() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      // `pos` is aligned to 16 bytes, so each particle is padded.
+      assert_eq!(record_info_for_type::<Particle>().size(), 32);
+
+      let particles = (0 .. 10_u32).map(|i| Particle {
+        id: i,
+        pos: [i as f32, -(i as f32), 0.5],
+      }).collect::<Vec<_>>();
+      let buffer = pewter.create_buffer::<Particle>("particles", 10)
+        .expect("Failed to create buffer.");
+      assert_eq!(buffer.len(), 10);
+      buffer.write_slice(&particles)
+        .expect("Failed to write buffer.");
+      let read = buffer.read_to_vec().await
+        .expect("Failed to read buffer.");
+      assert_eq!(read, particles);
+
+      // The buffer can be looked up by name with its own type.
+      let found = pewter.buffer::<Particle>("particles")
+        .expect("Failed to find buffer.");
+      assert_eq!(found.read_to_vec().await.unwrap(), particles);
+      assert!(matches!(pewter.buffer::<u32>("particles"),
+        Err(PewterError::BufferTypeMismatch { .. })));
+      assert!(matches!(pewter.buffer::<u32>("missing"),
+        Err(PewterError::UnknownPersistentBuffer(_))));
+      assert!(matches!(pewter.create_buffer::<u32>("particles", 4),
+        Err(PewterError::PersistentBufferNameCollision(_))));
+      assert!(matches!(buffer.write_slice(&[Particle::default(); 11]),
+        Err(PewterError::BufferLengthMismatch { .. })));
+      assert!(matches!(pewter.create_buffer::<u32>("huge", usize::MAX),
+        Err(PewterError::BufferTooLarge { .. })));
+
+      // Lengths which fit in a u32 are still checked against the
+      // buffer size limits of the device.
+      let max_size = pewter.device().limits().max_buffer_size;
+      let over_limit = (max_size / 4 + 1) as usize;
+      assert!(matches!(pewter.create_buffer::<u32>("big", over_limit),
+        Err(PewterError::BufferTooLarge { .. })));
+      let shape = BufferShape::new_2d(1 << 16, 1 << 16);
+      assert!(matches!(pewter.create_shaped_buffer::<u32>("big_2d", shape),
+        Err(PewterError::BufferTooLarge { .. })));
+      let shape = BufferShape::new_3d(u32::MAX, u32::MAX, u32::MAX);
+      assert!(matches!(pewter.create_shaped_buffer::<u32>("big_3d", shape),
+        Err(PewterError::BufferTooLarge { .. })));
+      let result = pewter.construct::<(), _>(|cons| {
+        cons.buffer::<u32>("big", over_limit) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferTooLarge { .. })));
+      let result = pewter.construct::<(), _>(|cons| {
+        cons.tail_buffer::<u32, u32>("big", over_limit) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferTooLarge { .. })));
+
+      // Writing an empty slice leaves the buffer unchanged.
+      buffer.write_slice(&[]).expect("Failed to write buffer.");
+      assert_eq!(buffer.read_to_vec().await.unwrap(), particles);
+    });
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Particle {
+  id: u32,
+  pos: [f32; 3],
+}
+impl DataType for Particle {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.record::<Self>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.record(self)
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.record(self)
+  }
+}
+impl RecordType for Particle {
+  fn specify_fields<FS: RecordTypeFieldSpecifier<Self>>(fs: &mut FS) {
+    fs.prim_field("id", |r| r.id, |r, id| r.id = id);
+    fs.prim_field("pos", |r| r.pos, |r, pos| r.pos = pos);
+  }
+}
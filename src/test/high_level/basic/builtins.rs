@@ -0,0 +1,138 @@
+use crate::{ Pewter, PewterConfig, lit, select };
+
+#[test]
+fn test_builtins() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_builtins = None;
+      pewter.declare(|decl| {
+        sh_builtins = Some(decl.shader_file("builtins", |decl_sh| {
+          let vals = decl_sh.use_buf::<f32>("vals") ?;
+          let ints = decl_sh.use_buf::<i32>("ints") ?;
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          // Only checked to be valid WGSL.
+          decl_sh.function::<f32, _>("shaped", |decl_sf| {
+            let x = decl_sf.let_("x", lit(0.25_f32));
+            let v = decl_sf.let_("v", lit([1.0_f32, -2.0]));
+            let edges = lit([0.0_f32, 0.5]).step(v.clone());
+            let ramp = v.clone().smoothstep(lit([-1.0, -1.0]), lit([1.0, 1.0]));
+            let near = edges.mix(ramp, v.clone().normalize())
+              .distance(v.clone().reflect(lit([0.0, 1.0])));
+            let turn = x.clone().atan2(lit(1.0)).sin().pow(x.clone().exp2());
+            let both = v.clone().gt(lit([0.0, 0.0])).any()
+              .and(v.ge(lit([-2.0, -2.0])).all());
+            let n = lit([3_i32, -4]).dot(lit([1, 2])).abs()
+              .first_trailing_bit();
+            decl_sf.if_(both, |decl_sf| {
+              decl_sf.return_stmt(near.fma(turn, x.fract().inverse_sqrt()));
+              Ok(())
+            }) ?;
+            decl_sf.return_stmt(select(lit(0.0), lit(1.0), n.lt(lit(2))));
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("builtins_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let p = decl_sf.let_("p", pos.load(id.clone()));
+            let v = vals.load(id.clone()).floor()
+              .clamp(lit(1.0), lit(8.0)).sqrt() + p.clone().length();
+            decl_sf.store(&vals, id.clone(), v);
+            decl_sf.store(&pos, id.clone(), p.cross(lit([0.0, 0.0, 1.0])));
+
+            let i = decl_sf.let_("i", ints.load(id.clone()));
+            let counted = select(
+              i.clone().abs(),
+              i.clone().count_one_bits(),
+              i.lt(lit(0)));
+            decl_sf.store(&ints, id.clone(), counted);
+
+            let w = decl_sf.let_("w", words.load(id.clone()));
+            let bits = w.clone().first_leading_bit()
+              + (w.reverse_bits() >> lit(16_u32));
+            decl_sf.store(&words, id, bits);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_builtins = sh_builtins.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_builtins) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains(
+        "(sqrt(clamp(floor(vals[global_id]), 1.0f, 8.0f)) + length(p))"));
+      assert!(text.contains("select(abs(i), countOneBits(i), (i < 0i))"));
+      assert!(text.contains("step(v, vec2<f32>(0.0f, 0.5f))"));
+      assert!(text.contains(
+        "smoothstep(vec2<f32>((-1.0f), (-1.0f)), vec2<f32>(1.0f, 1.0f), v)"));
+
+      let n = 32_usize;
+      let vals_in = (0 .. n).map(|i| i as f32 * 0.75).collect::<Vec<_>>();
+      let pos_in = (0 .. n)
+        .map(|i| [i as f32, 2.0 - i as f32, 0.5])
+        .collect::<Vec<_>>();
+      let ints_in = (0 .. n as i32).map(|i| i * 37 - 500).collect::<Vec<_>>();
+      let words_in = (0 .. n as u32)
+        .map(|i| i.wrapping_mul(0x9e37_79b9) >> (i % 32))
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let vals = cons.buffer::<f32>("vals", n) ?;
+        let ints = cons.buffer::<i32>("ints", n) ?;
+        let words = cons.buffer::<u32>("words", n) ?;
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        cons.write_buffer(&vals, &vals_in) ?;
+        cons.write_buffer(&ints, &ints_in) ?;
+        cons.write_buffer(&words, &words_in) ?;
+        cons.write_buffer(&pos, &pos_in) ?;
+        cons.dispatch(&sh_builtins, "builtins_entry", &[n as u32]) ?
+          .bind("vals", &vals) ?
+          .bind("ints", &ints) ?
+          .bind("words", &words) ?
+          .bind("pos", &pos) ?;
+        let vals_back = cons.read_buffer(&vals);
+        let ints_back = cons.read_buffer(&ints);
+        let words_back = cons.read_buffer(&words);
+        let pos_back = cons.read_buffer(&pos);
+        cons.result(move |out| {
+          (out.get(vals_back), out.get(ints_back),
+           out.get(words_back), out.get(pos_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (vals_out, ints_out, words_out, pos_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let [x, y, z] = pos_in[i];
+        let expected = vals_in[i].floor().clamp(1.0, 8.0).sqrt()
+          + (x * x + y * y + z * z).sqrt();
+        assert!((vals_out[i] - expected).abs() <= expected * 1e-5,
+          "vals[{}] = {}, expected {}", i, vals_out[i], expected);
+        assert_eq!(pos_out[i], [y, -x, 0.0]);
+
+        let int = ints_in[i];
+        let counted = if int < 0 { int.count_ones() as i32 } else { int.abs() };
+        assert_eq!(ints_out[i], counted);
+
+        let word = words_in[i];
+        let leading = if word == 0 {
+          u32::MAX
+        } else {
+          31 - word.leading_zeros()
+        };
+        assert_eq!(words_out[i],
+          leading.wrapping_add(word.reverse_bits() >> 16),
+          "words[{}] from {:#x}", i, word);
+      }
+    });
+}
@@ -0,0 +1,103 @@
+use crate::{ Pewter, PewterConfig, PewterError, lit };
+
+#[test]
+fn test_function_calls() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_calls = None;
+      pewter.declare(|decl| {
+        sh_calls = Some(decl.shader_file("calls", |decl_sh| {
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          let add_u32_u32 = decl_sh.function::<u32, _>("add_u32_u32",
+            |decl_sf| {
+              let a = decl_sf.use_arg::<u32>("a") ?;
+              let b = decl_sf.use_arg::<u32>("b") ?;
+              decl_sf.return_stmt(a + b);
+              Ok(())
+            }) ?;
+          let seven = decl_sh.function::<u32, _>("seven", |decl_sf| {
+            decl_sf.return_stmt(lit(7_u32));
+            Ok(())
+          }) ?;
+          let scale = decl_sh.function::<[f32; 3], _>("scale", |decl_sf| {
+            let v = decl_sf.use_arg::<[f32; 3]>("v") ?;
+            let s = decl_sf.use_arg::<f32>("s") ?;
+            decl_sf.return_stmt(v * s);
+            Ok(())
+          }) ?;
+          // Functions can call those declared before them.
+          let add_seven = decl_sh.function::<u32, _>("add_seven",
+            |decl_sf| {
+              let x = decl_sf.use_arg::<u32>("x") ?;
+              let result = decl_sf.call(&add_u32_u32,
+                (x, decl_sf.call(&seven, ()) ?)) ?;
+              decl_sf.return_stmt(result);
+              Ok(())
+            }) ?;
+          decl_sh.entry_function_1d("calls_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let word = decl_sf.call(&add_seven, words.load(id.clone())) ?;
+            decl_sf.store(&words, id.clone(), word);
+            let scaled = decl_sf.call(&scale,
+              (pos.load(id.clone()), lit(0.5_f32))) ?;
+            decl_sf.store(&pos, id.clone(), scaled);
+
+            let result = decl_sf.call(&scale, (pos.load(id.clone()), id));
+            assert!(matches!(result,
+              Err(PewterError::CallArgumentMismatch {
+                ref expected, ref actual, ..
+              }) if expected == "vec3<f32>, f32"
+                && actual == "vec3<f32>, u32"));
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_calls = sh_calls.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_calls) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("return add_u32_u32(x, seven());"));
+      assert!(text.contains(
+        "words[global_id] = add_seven(words[global_id]);"));
+      assert!(text.contains(
+        "pos[global_id] = scale(pos[global_id], 0.5f);"));
+
+      let n = 16_usize;
+      let words_in = (0 .. n as u32).map(|i| i * 13).collect::<Vec<_>>();
+      let pos_in = (0 .. n).map(|i| [i as f32, -1.0, 2.5])
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let words = cons.buffer::<u32>("words", n) ?;
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        cons.write_buffer(&words, &words_in) ?;
+        cons.write_buffer(&pos, &pos_in) ?;
+        cons.dispatch(&sh_calls, "calls_entry", &[n as u32]) ?
+          .bind("words", &words) ?
+          .bind("pos", &pos) ?;
+        let words_back = cons.read_buffer(&words);
+        let pos_back = cons.read_buffer(&pos);
+        cons.result(move |out| (out.get(words_back), out.get(pos_back)));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (words_out, pos_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        assert_eq!(words_out[i], words_in[i] + 7);
+        let p = pos_in[i];
+        assert_eq!(pos_out[i], [p[0] * 0.5, p[1] * 0.5, p[2] * 0.5]);
+      }
+    });
+}
@@ -0,0 +1,133 @@
+
+use crate::{ Pewter, PewterConfig, PewterError };
+
+#[test]
+fn test_construct_roundtrip() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_noop = None;
+      let mut sh_triple = None;
+      pewter.declare(|decl| {
+        sh_triple = Some(decl.shader_file("triple", |decl_sh| {
+          let input = decl_sh.use_buf_read::<u32>("input") ?;
+          let output = decl_sh.use_buf::<u32>("output") ?;
+          decl_sh.entry_function_1d("triple_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let value = input.load(id.clone());
+            let tripled = value.clone() + value.clone() + value;
+            decl_sf.store(&output, id, tripled);
+            Ok(())
+          })
+        }) ?);
+        sh_noop = Some(decl.shader_file("noop", |decl_sh| {
+          decl_sh.use_buf::<u32>("data") ?;
+          decl_sh.entry_function_1d("noop_entry", |_decl_sf| Ok(()))
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_noop = sh_noop.unwrap();
+      let sh_triple = sh_triple.unwrap();
+
+      let input = (0 .. 100_u32).map(|i| i * 7).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let data = cons.buffer::<u32>("data", input.len()) ?;
+        cons.write_buffer(&data, &input) ?;
+        cons.dispatch(&sh_noop, "noop_entry", &[input.len() as u32]) ?
+          .bind("data", &data) ?;
+        let readback = cons.read_buffer(&data);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      assert_eq!(output, input);
+
+      // Entrypoints load from and store to their buffers.
+      let construct = pewter.construct(|cons| {
+        let in_buf = cons.buffer::<u32>("input", input.len()) ?;
+        let out_buf = cons.buffer::<u32>("output", input.len()) ?;
+        cons.write_buffer(&in_buf, &input) ?;
+        cons.dispatch(&sh_triple, "triple_entry", &[input.len() as u32]) ?
+          .bind("input", &in_buf) ?
+          .bind("output", &out_buf) ?;
+        let readback = cons.read_buffer(&out_buf);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      let expected = input.iter().map(|v| v * 3).collect::<Vec<_>>();
+      assert_eq!(output, expected);
+
+      // The pipeline compiled for an entrypoint is reused by later
+      // dispatches, including ones prepared from other threads.
+      let nine_times = || pewter.construct(|cons| {
+        let in_buf = cons.buffer::<u32>("input", input.len()) ?;
+        let mid_buf = cons.buffer::<u32>("middle", input.len()) ?;
+        let out_buf = cons.buffer::<u32>("output", input.len()) ?;
+        cons.write_buffer(&in_buf, &input) ?;
+        let grid = [input.len() as u32];
+        cons.dispatch(&sh_triple, "triple_entry", &grid) ?
+          .bind("input", &in_buf) ?
+          .bind("output", &mid_buf) ?;
+        cons.dispatch(&sh_triple, "triple_entry", &grid) ?
+          .bind("input", &mid_buf) ?
+          .bind("output", &out_buf) ?;
+        let readback = cons.read_buffer(&out_buf);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      });
+      std::thread::scope(|scope| {
+        let threads = (0 .. 4)
+          .map(|_| scope.spawn(|| nine_times().map(|_| ())))
+          .collect::<Vec<_>>();
+        for thread in threads {
+          thread.join().expect("Construct thread panicked.")
+            .expect("Failed to make construct.");
+        }
+      });
+      let output = nine_times().expect("Failed to make construct.")
+        .perform().await
+        .expect("Failed to perform construct.");
+      let expected = input.iter().map(|v| v * 9).collect::<Vec<_>>();
+      assert_eq!(output, expected);
+
+      // Dispatch errors are reported when the construct is made.
+      let result = pewter.construct::<(), _>(|cons| {
+        cons.dispatch(&sh_noop, "noop_entry", &[4]) ?;
+        cons.result(|_| ());
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::UnboundBuffer { .. })));
+
+      // A buffer can't be both read-only and read-write in a dispatch.
+      let result = pewter.construct::<(), _>(|cons| {
+        let buf = cons.buffer::<u32>("both", 4) ?;
+        cons.dispatch(&sh_triple, "triple_entry", &[4]) ?
+          .bind("input", &buf) ?
+          .bind("output", &buf) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferAccessConflict { .. })));
+
+      let result = pewter.construct::<(), _>(|cons| {
+        cons.dispatch(&sh_noop, "missing", &[4]) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::UnknownEntryPoint { .. })));
+
+      let result = pewter.construct::<(), _>(|cons| {
+        cons.dispatch(&sh_noop, "noop_entry", &[u32::MAX]) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::GridTooLarge { .. })));
+    });
+}
@@ -0,0 +1,178 @@
+use crate::{ Pewter, PewterConfig, PewterError };
+
+#[test]
+fn test_control_flow() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_flow = None;
+      pewter.declare(|decl| {
+        sh_flow = Some(decl.shader_file("flow", |decl_sh| {
+          let sums = decl_sh.use_buf::<u32>("sums") ?;
+          let lo = decl_sh.use_buf_read::<u32>("lo") ?;
+          let hi = decl_sh.use_buf_read::<u32>("hi") ?;
+          let skip = decl_sh.use_buf_read::<u32>("skip") ?;
+          decl_sh.function::<u32, _>("clamp_to", |decl_sf| {
+            let x = decl_sf.use_arg::<u32>("x") ?;
+            let limit = decl_sf.use_arg::<u32>("limit") ?;
+            decl_sf.if_(x.clone().gt(limit.clone()), |then| {
+              then.return_stmt(limit);
+              Ok(())
+            }) ?.else_(|els| {
+              els.return_stmt(x);
+              Ok(())
+            })
+          }) ?;
+          // Each loop is in its own entry function, as some drivers
+          // lose stores to a buffer made in one loop when it is read
+          // in a following one.
+          decl_sh.entry_function_1d("sum_range", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let total = sums.load(id.clone());
+            // Sum the range, leaving out `skip`.
+            decl_sf.for_range(lo.load(id.clone()), hi.load(id.clone()),
+              |body, i| {
+                body.if_(i.clone().eq(skip.load(id.clone())), |then| {
+                  then.continue_()
+                }) ?;
+                body.store(&sums, id.clone(), total.clone() + i);
+                Ok(())
+              })
+          }) ?;
+          decl_sh.entry_function_1d("step_to_hi", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let total = sums.load(id.clone());
+            // Step by `lo` until reaching `hi`.
+            decl_sf.while_(total.clone().lt(hi.load(id.clone())), |body| {
+              body.store(&sums, id.clone(),
+                total.clone() + lo.load(id.clone()));
+              Ok(())
+            })
+          }) ?;
+          decl_sh.entry_function_1d("step_past_skip", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let total = sums.load(id.clone());
+            // Step by `hi` until passing `skip`.
+            decl_sf.loop_(|body| {
+              body.if_(total.clone().gt(skip.load(id.clone())), |then| {
+                then.break_()
+              }) ?.else_(|els| {
+                els.store(&sums, id.clone(),
+                  total.clone() + hi.load(id.clone()));
+                Ok(())
+              })
+            })
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_flow = sh_flow.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_flow) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains(concat!(
+        "  if ((x > limit)) {\n",
+        "    return limit;\n",
+        "  } else {\n",
+        "    return x;\n",
+        "  }\n")));
+      assert!(text.contains(concat!(
+        "  for (var x_counter: u32 = lo[global_id]; ",
+        "x_counter < hi[global_id]; x_counter++) {\n",
+        "    if ((x_counter == skip[global_id])) {\n",
+        "      continue;\n",
+        "    }\n")));
+      assert!(text.contains(
+        "  while ((sums[global_id] < hi[global_id])) {\n"));
+      assert!(text.contains(concat!(
+        "  loop {\n",
+        "    if ((sums[global_id] > skip[global_id])) {\n",
+        "      break;\n")));
+
+      let n = 24_usize;
+      let lo_in = (0 .. n as u32).map(|i| 1 + i % 3).collect::<Vec<_>>();
+      let hi_in = (0 .. n as u32).map(|i| lo_in[i as usize] + i % 7)
+        .collect::<Vec<_>>();
+      let skip_in = (0 .. n as u32).map(|i| lo_in[i as usize] + i % 4)
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let sums = cons.buffer::<u32>("sums", n) ?;
+        let lo = cons.buffer::<u32>("lo", n) ?;
+        let hi = cons.buffer::<u32>("hi", n) ?;
+        let skip = cons.buffer::<u32>("skip", n) ?;
+        cons.write_buffer(&sums, &vec![0; n]) ?;
+        cons.write_buffer(&lo, &lo_in) ?;
+        cons.write_buffer(&hi, &hi_in) ?;
+        cons.write_buffer(&skip, &skip_in) ?;
+        for entry in ["sum_range", "step_to_hi", "step_past_skip"] {
+          cons.dispatch(&sh_flow, entry, &[n as u32]) ?
+            .bind("sums", &sums) ?
+            .bind("lo", &lo) ?
+            .bind("hi", &hi) ?
+            .bind("skip", &skip) ?;
+        }
+        let sums_back = cons.read_buffer(&sums);
+        cons.result(move |out| out.get(sums_back));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let sums_out = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let (lo, hi, skip) = (lo_in[i], hi_in[i], skip_in[i]);
+        let mut total = (lo .. hi).filter(|&x| x != skip).sum::<u32>();
+        while total < hi { total += lo; }
+        while total <= skip { total += hi; }
+        assert_eq!(sums_out[i], total, "Unexpected sum for {}", i);
+      }
+    });
+}
+
+#[test]
+fn test_loop_control_outside_loop() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("stray", |decl_sh| {
+          let cond = decl_sh.use_buf_read::<u32>("cond") ?;
+          decl_sh.entry_function_1d("stray_break", |decl_sf| {
+            let id = decl_sf.get_arg();
+            decl_sf.if_(id.clone().lt(cond.load(id)), |then| then.break_())
+              .map(|_| ())
+          })
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::BreakOutsideLoop { ref function })
+          if function.as_ref() == "stray_break"));
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("stray", |decl_sh| {
+          decl_sh.entry_function_1d("stray_continue", |decl_sf| {
+            decl_sf.continue_()
+          })
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::ContinueOutsideLoop { ref function })
+          if function.as_ref() == "stray_continue"));
+    });
+}
@@ -0,0 +1,97 @@
+use crate::{ Pewter, PewterConfig, lit };
+
+#[test]
+fn test_conversions() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_conv = None;
+      pewter.declare(|decl| {
+        sh_conv = Some(decl.shader_file("conv", |decl_sh| {
+          let vals = decl_sh.use_buf::<f32>("vals") ?;
+          let ints = decl_sh.use_buf::<i32>("ints") ?;
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          // Only checked to be valid WGSL.
+          decl_sh.function::<[f32; 2], _>("vectors", |decl_sf| {
+            let flags = lit([0_u32, 3]).cast::<[bool; 2]>();
+            let bits = lit([1_i32, -1]).bitcast::<[f32; 2]>();
+            decl_sf.return_stmt(flags.cast::<[f32; 2]>() + bits);
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("conv_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let v = decl_sf.let_("v", vals.load(id.clone()));
+            decl_sf.store(&ints, id.clone(),
+              v.clone().cast::<i32>() + id.clone().cast::<i32>());
+            decl_sf.store(&words, id.clone(), v.bitcast::<u32>());
+            let past = id.clone().gt(lit(3_u32)).cast::<f32>();
+            decl_sf.store(&vals, id.clone(),
+              id.clone().cast::<f32>() * lit(0.5_f32) + past);
+            let p = pos.load(id.clone()).cast::<[i32; 3]>();
+            decl_sf.store(&pos, id, p.cast::<[f32; 3]>());
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_conv = sh_conv.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_conv) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("(i32(v) + i32(global_id))"));
+      assert!(text.contains("words[global_id] = bitcast<u32>(v);"));
+      assert!(text.contains("vec3<f32>(vec3<i32>(pos[global_id]))"));
+      assert!(text.contains("bitcast<vec2<f32>>(vec2<i32>(1i, (-1i)))"));
+
+      let n = 16_usize;
+      let vals_in = (0 .. n)
+        .map(|i| (i as f32 - 8.0) * 1.375)
+        .collect::<Vec<_>>();
+      let pos_in = (0 .. n)
+        .map(|i| [i as f32 * 0.7, -(i as f32) * 0.3, 2.5])
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let vals = cons.buffer::<f32>("vals", n) ?;
+        let ints = cons.buffer::<i32>("ints", n) ?;
+        let words = cons.buffer::<u32>("words", n) ?;
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        cons.write_buffer(&vals, &vals_in) ?;
+        cons.write_buffer(&pos, &pos_in) ?;
+        cons.dispatch(&sh_conv, "conv_entry", &[n as u32]) ?
+          .bind("vals", &vals) ?
+          .bind("ints", &ints) ?
+          .bind("words", &words) ?
+          .bind("pos", &pos) ?;
+        let vals_back = cons.read_buffer(&vals);
+        let ints_back = cons.read_buffer(&ints);
+        let words_back = cons.read_buffer(&words);
+        let pos_back = cons.read_buffer(&pos);
+        cons.result(move |out| {
+          (out.get(vals_back), out.get(ints_back),
+           out.get(words_back), out.get(pos_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (vals_out, ints_out, words_out, pos_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let past = if i > 3 { 1.0 } else { 0.0 };
+        assert_eq!(vals_out[i], i as f32 * 0.5 + past);
+        // Floats are truncated towards zero.
+        assert_eq!(ints_out[i], vals_in[i] as i32 + i as i32);
+        assert_eq!(words_out[i], vals_in[i].to_bits());
+        assert_eq!(pos_out[i], pos_in[i].map(|c| c as i32 as f32));
+      }
+    });
+}
@@ -0,0 +1,120 @@
+use crate::{ Pewter, PewterConfig, DataType, RecordType, lit };
+
+#[test]
+fn test_literals() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let exact = [0.1_f32, 1.0 / 3.0, -f32::MAX];
+      let marker = Marker { id: 7, offset: [-1, 2], weight: 1e-7 };
+      let mut sh_lits = None;
+      pewter.declare(|decl| {
+        sh_lits = Some(decl.shader_file("lits", |decl_sh| {
+          let vals = decl_sh.use_buf::<f32>("vals") ?;
+          let ints = decl_sh.use_buf::<i32>("ints") ?;
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          // Marker is only used in the body, but must still be declared.
+          decl_sh.function::<u32, _>("marker_id", |decl_sf| {
+            decl_sf.let_("marker", lit(marker));
+            decl_sf.return_stmt(lit(marker.id));
+            Ok(())
+          }) ?;
+          decl_sh.function::<f32, _>("infinity", |decl_sf| {
+            decl_sf.return_stmt(lit(f32::INFINITY));
+            Ok(())
+          }) ?;
+          decl_sh.function::<[bool; 2], _>("flags", |decl_sf| {
+            decl_sf.return_stmt(lit([true, false]));
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("lits_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let v = vals.load(id.clone()) * decl_sf.lit(1.5_f32)
+              - lit(-0.25_f32);
+            decl_sf.store(&vals, id.clone(), v);
+            let i = ints.load(id.clone()) * lit(3_i32) - lit(-7_i32)
+              + lit(i32::MIN);
+            decl_sf.store(&ints, id.clone(), i);
+            let w = words.load(id.clone()) * lit(3_u32) + lit(u32::MAX);
+            decl_sf.store(&words, id.clone(), w);
+            decl_sf.store(&pos, id, lit(exact));
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_lits = sh_lits.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_lits) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("((vals[global_id] * 1.5f) - (-0.25f))"));
+      assert!(text.contains(
+        "(((ints[global_id] * 3i) - (-7i)) + (-2147483648i))"));
+      assert!(text.contains("((words[global_id] * 3u) + 4294967295u)"));
+      assert!(text.contains(
+        "pos[global_id] = vec3<f32>(0.1f, 0.33333334f, (-3.4028235e38f));"));
+      assert!(text.contains("_Marker {\n  id: u32,"));
+      assert!(text.contains("_Marker(7u, vec2<i32>((-1i), 2i), 1e-7f);"));
+      assert!(text.contains("return bitcast<f32>(2139095040u);"));
+      assert!(text.contains("return vec2<bool>(true, false);"));
+
+      let n = 16_usize;
+      let vals_in = (0 .. n).map(|i| i as f32 * 0.75).collect::<Vec<_>>();
+      let ints_in = (0 .. n as i32).map(|i| i * 101 - 700).collect::<Vec<_>>();
+      let words_in = (0 .. n as u32).map(|i| i * 977).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let vals = cons.buffer::<f32>("vals", n) ?;
+        let ints = cons.buffer::<i32>("ints", n) ?;
+        let words = cons.buffer::<u32>("words", n) ?;
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        cons.write_buffer(&vals, &vals_in) ?;
+        cons.write_buffer(&ints, &ints_in) ?;
+        cons.write_buffer(&words, &words_in) ?;
+        cons.dispatch(&sh_lits, "lits_entry", &[n as u32]) ?
+          .bind("vals", &vals) ?
+          .bind("ints", &ints) ?
+          .bind("words", &words) ?
+          .bind("pos", &pos) ?;
+        let vals_back = cons.read_buffer(&vals);
+        let ints_back = cons.read_buffer(&ints);
+        let words_back = cons.read_buffer(&words);
+        let pos_back = cons.read_buffer(&pos);
+        cons.result(move |out| {
+          (out.get(vals_back), out.get(ints_back),
+           out.get(words_back), out.get(pos_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (vals_out, ints_out, words_out, pos_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        assert_eq!(vals_out[i], vals_in[i] * 1.5 + 0.25);
+        assert_eq!(ints_out[i],
+          (ints_in[i] * 3 + 7).wrapping_add(i32::MIN));
+        assert_eq!(words_out[i], (words_in[i] * 3).wrapping_add(u32::MAX));
+        // Float literals read back bit for bit.
+        for c in 0 .. 3 {
+          assert_eq!(pos_out[i][c].to_bits(), exact[c].to_bits());
+        }
+      }
+    });
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Marker {
+  id: u32,
+  offset: [i32; 2],
+  weight: f32,
+}
@@ -0,0 +1,127 @@
+use crate::{ Pewter, PewterConfig, lit };
+
+type Mat3 = [[f32; 3]; 3];
+
+#[test]
+fn test_matrices() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_mat = None;
+      pewter.declare(|decl| {
+        sh_mat = Some(decl.shader_file("mat", |decl_sh| {
+          let mats = decl_sh.use_buf::<Mat3>("mats") ?;
+          let vecs = decl_sh.use_buf::<[f32; 3]>("vecs") ?;
+          let dets = decl_sh.use_buf::<f32>("dets") ?;
+          let wide = decl_sh.use_buf::<[[f32; 2]; 3]>("wide") ?;
+          decl_sh.entry_function_1d("mat_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let m = decl_sf.let_("m", mats.load(id.clone()));
+            let v = decl_sf.let_("v", vecs.load(id.clone()));
+            decl_sf.store(&vecs, id.clone(),
+              m.clone() * v.clone() + v * m.clone());
+            decl_sf.store(&dets, id.clone(), m.clone().determinant());
+            decl_sf.store(&mats, id.clone(),
+              m.clone().transpose() * m.clone() * lit(0.5_f32) + m);
+            let w = wide.load(id.clone());
+            decl_sf.store(&wide, id, w.clone() * lit(2.0_f32) + w);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_mat = sh_mat.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_mat) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("((m * v) + (v * m))"));
+      assert!(text.contains("dets[global_id] = determinant(m);"));
+      assert!(text.contains("(((transpose(m) * m) * 0.5f) + m)"));
+
+      let n = 16_usize;
+      let mats_in = (0 .. n)
+        .map(|i| {
+          let f = i as f32;
+          [[1.0 + f, 2.0, -1.0], [0.5, f * 0.25, 3.0], [2.0, -f, 1.5]]
+        })
+        .collect::<Vec<_>>();
+      let vecs_in = (0 .. n)
+        .map(|i| [1.0, i as f32 * 0.5, -2.0])
+        .collect::<Vec<_>>();
+      let wide_in = (0 .. n)
+        .map(|i| {
+          let f = i as f32;
+          [[f, 1.0], [-f, 0.25], [2.0, f * f]]
+        })
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let mats = cons.buffer::<Mat3>("mats", n) ?;
+        let vecs = cons.buffer::<[f32; 3]>("vecs", n) ?;
+        let dets = cons.buffer::<f32>("dets", n) ?;
+        let wide = cons.buffer::<[[f32; 2]; 3]>("wide", n) ?;
+        cons.write_buffer(&mats, &mats_in) ?;
+        cons.write_buffer(&vecs, &vecs_in) ?;
+        cons.write_buffer(&wide, &wide_in) ?;
+        cons.dispatch(&sh_mat, "mat_entry", &[n as u32]) ?
+          .bind("mats", &mats) ?
+          .bind("vecs", &vecs) ?
+          .bind("dets", &dets) ?
+          .bind("wide", &wide) ?;
+        let mats_back = cons.read_buffer(&mats);
+        let vecs_back = cons.read_buffer(&vecs);
+        let dets_back = cons.read_buffer(&dets);
+        let wide_back = cons.read_buffer(&wide);
+        cons.result(move |out| {
+          (out.get(mats_back), out.get(vecs_back),
+           out.get(dets_back), out.get(wide_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (mats_out, vecs_out, dets_out, wide_out) = construct.perform()
+        .await
+        .expect("Failed to perform construct.");
+
+      let close = |a: f32, b: f32| (a - b).abs() <= 1e-4 * (1.0 + b.abs());
+      for i in 0 .. n {
+        let m = mats_in[i];
+        let v = vecs_in[i];
+        // Matrices are arrays of columns, so `m[c][r]` is row `r` of
+        // column `c`.
+        for r in 0 .. 3 {
+          let expected = (0 .. 3)
+            .map(|k| m[k][r] * v[k] + v[k] * m[r][k])
+            .sum::<f32>();
+          assert!(close(vecs_out[i][r], expected),
+            "vecs[{}][{}] = {}, expected {}", i, r, vecs_out[i][r], expected);
+        }
+
+        let det = m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2])
+          - m[1][0] * (m[0][1] * m[2][2] - m[2][1] * m[0][2])
+          + m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2]);
+        assert!(close(dets_out[i], det),
+          "dets[{}] = {}, expected {}", i, dets_out[i], det);
+
+        for c in 0 .. 3 {
+          for r in 0 .. 3 {
+            // (transpose(m) * m)[c][r] is the dot of columns r and c.
+            let dot = (0 .. 3).map(|k| m[r][k] * m[c][k]).sum::<f32>();
+            let expected = dot * 0.5 + m[c][r];
+            assert!(close(mats_out[i][c][r], expected),
+              "mats[{}][{}][{}] = {}, expected {}",
+              i, c, r, mats_out[i][c][r], expected);
+          }
+        }
+
+        assert_eq!(wide_out[i], wide_in[i].map(|col| col.map(|x| x * 3.0)));
+      }
+    });
+}
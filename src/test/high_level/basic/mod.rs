@@ -1,2 +1,19 @@
 
-mod pewter;
\ No newline at end of file
+mod pewter;
+mod construct;
+mod buffer;
+mod shape;
+mod access;
+mod record;
+mod ops;
+mod control;
+mod vars;
+mod literal;
+mod call;
+mod builtins;
+mod convert;
+mod swizzle;
+mod matrix;
+mod array;
+mod tail;
+mod atomic;
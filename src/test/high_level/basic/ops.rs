@@ -0,0 +1,263 @@
+use crate::{ Pewter, PewterConfig, PewterError };
+
+#[test]
+fn test_arithmetic_ops() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_arith = None;
+      pewter.declare(|decl| {
+        sh_arith = Some(decl.shader_file("arith", |decl_sh| {
+          // Results are stored in place, staying within the default
+          // limit of four storage buffers.
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          let scale = decl_sh.use_buf_read::<f32>("scale") ?;
+          let lhs = decl_sh.use_buf::<i32>("lhs") ?;
+          let rhs = decl_sh.use_buf_read::<i32>("rhs") ?;
+          decl_sh.entry_function_1d("arith_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let v = pos.load(id.clone());
+            let s = scale.load(id.clone());
+            let moved = v.clone() * s.clone() + s.clone() * v.clone()
+              - v / s;
+            decl_sf.store(&pos, id.clone(), moved);
+
+            let a = lhs.load(id.clone());
+            let b = rhs.load(id.clone());
+            let mixed = -(a.clone() % b.clone()) + a.clone() * b.clone()
+              - a / b;
+            decl_sf.store(&lhs, id, mixed);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_arith = sh_arith.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_arith) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("(-(lhs[global_id] % rhs[global_id]))"));
+
+      let n = 32_usize;
+      let pos_in = (0 .. n)
+        .map(|i| [i as f32, 1.0 - i as f32, 0.25])
+        .collect::<Vec<_>>();
+      let scale_in = (0 .. n).map(|i| 1.0 + i as f32 * 0.5).collect::<Vec<_>>();
+      // The sign of a remainder of negative operands differs between
+      // backends, so only the negation produces negative values.
+      let lhs_in = (0 .. n).map(|i| i as i32 * 7 + 3).collect::<Vec<_>>();
+      let rhs_in = (0 .. n).map(|i| i as i32 % 5 + 1).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        let scale = cons.buffer::<f32>("scale", n) ?;
+        let lhs = cons.buffer::<i32>("lhs", n) ?;
+        let rhs = cons.buffer::<i32>("rhs", n) ?;
+        cons.write_buffer(&pos, &pos_in) ?;
+        cons.write_buffer(&scale, &scale_in) ?;
+        cons.write_buffer(&lhs, &lhs_in) ?;
+        cons.write_buffer(&rhs, &rhs_in) ?;
+        cons.dispatch(&sh_arith, "arith_entry", &[n as u32]) ?
+          .bind("pos", &pos) ?
+          .bind("scale", &scale) ?
+          .bind("lhs", &lhs) ?
+          .bind("rhs", &rhs) ?;
+        let pos_back = cons.read_buffer(&pos);
+        let int_back = cons.read_buffer(&lhs);
+        cons.result(move |out| (out.get(pos_back), out.get(int_back)));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (out_pos, out_int) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let (v, s) = (pos_in[i], scale_in[i]);
+        for c in 0 .. 3 {
+          let expected = v[c] * s + s * v[c] - v[c] / s;
+          assert!((out_pos[i][c] - expected).abs() < 1e-4,
+            "Unexpected component {} of {}: {:?}", c, i, out_pos[i]);
+        }
+        let (a, b) = (lhs_in[i], rhs_in[i]);
+        assert_eq!(out_int[i], -(a % b) + a * b - a / b);
+      }
+    });
+}
+
+#[test]
+fn test_bitwise_ops() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_bits = None;
+      pewter.declare(|decl| {
+        sh_bits = Some(decl.shader_file("bits", |decl_sh| {
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          let amounts = decl_sh.use_buf_read::<u32>("amounts") ?;
+          let ints = decl_sh.use_buf::<i32>("ints") ?;
+          let pairs = decl_sh.use_buf::<[u32; 2]>("pairs") ?;
+          // Vectors are shifted by vectors of u32.
+          decl_sh.function::<[i32; 2], _>("shift_pair", |decl_sf| {
+            let value = decl_sf.use_arg::<[i32; 2]>("value") ?;
+            let amount = decl_sf.use_arg::<[u32; 2]>("amount") ?;
+            decl_sf.return_stmt(value << amount);
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("bits_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let x = words.load(id.clone());
+            let n = amounts.load(id.clone());
+            let hashed = ((x.clone() << n.clone()) ^ (x.clone() >> n.clone()))
+              | (!x & n.clone());
+            decl_sf.store(&words, id.clone(), hashed);
+
+            let i = ints.load(id.clone());
+            let shifted = (i.clone() >> n.clone()) & !(i << n);
+            decl_sf.store(&ints, id.clone(), shifted);
+
+            let pair = pairs.load(id.clone());
+            decl_sf.store(&pairs, id, !pair);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_bits = sh_bits.unwrap();
+
+      let n = 40_usize;
+      let words_in = (0 .. n as u32)
+        .map(|i| i.wrapping_mul(2654435761))
+        .collect::<Vec<_>>();
+      let amounts_in = (0 .. n as u32).map(|i| i % 32).collect::<Vec<_>>();
+      let ints_in = (0 .. n as i32).map(|i| i * 1237 - 20000).collect::<Vec<_>>();
+      let pairs_in = (0 .. n as u32).map(|i| [i, !i]).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let words = cons.buffer::<u32>("words", n) ?;
+        let amounts = cons.buffer::<u32>("amounts", n) ?;
+        let ints = cons.buffer::<i32>("ints", n) ?;
+        let pairs = cons.buffer::<[u32; 2]>("pairs", n) ?;
+        cons.write_buffer(&words, &words_in) ?;
+        cons.write_buffer(&amounts, &amounts_in) ?;
+        cons.write_buffer(&ints, &ints_in) ?;
+        cons.write_buffer(&pairs, &pairs_in) ?;
+        cons.dispatch(&sh_bits, "bits_entry", &[n as u32]) ?
+          .bind("words", &words) ?
+          .bind("amounts", &amounts) ?
+          .bind("ints", &ints) ?
+          .bind("pairs", &pairs) ?;
+        let words_back = cons.read_buffer(&words);
+        let ints_back = cons.read_buffer(&ints);
+        let pairs_back = cons.read_buffer(&pairs);
+        cons.result(move |out| {
+          (out.get(words_back), out.get(ints_back), out.get(pairs_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (words_out, ints_out, pairs_out) = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let (x, s) = (words_in[i], amounts_in[i]);
+        assert_eq!(words_out[i], ((x << s) ^ (x >> s)) | (!x & s));
+        let v = ints_in[i];
+        assert_eq!(ints_out[i], (v >> s) & !(v << s));
+        assert_eq!(pairs_out[i], [!pairs_in[i][0], !pairs_in[i][1]]);
+      }
+    });
+}
+
+#[test]
+fn test_compare_ops() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_cmp = None;
+      pewter.declare(|decl| {
+        sh_cmp = Some(decl.shader_file("cmp", |decl_sh| {
+          decl_sh.function::<bool, _>("in_range", |decl_sf| {
+            let x = decl_sf.use_arg::<f32>("x") ?;
+            let lo = decl_sf.use_arg::<f32>("lo") ?;
+            let hi = decl_sf.use_arg::<f32>("hi") ?;
+            let inside = lo.clone().le(x.clone()).and(x.clone().lt(hi.clone()));
+            let at_edge = x.clone().eq(lo).or(x.eq(hi));
+            decl_sf.return_stmt(inside.and(at_edge.not()));
+            Ok(())
+          }) ?;
+          decl_sh.function::<[bool; 3], _>("differs", |decl_sf| {
+            let a = decl_sf.use_arg::<[i32; 3]>("a") ?;
+            let b = decl_sf.use_arg::<[i32; 3]>("b") ?;
+            let c = decl_sf.use_arg::<[i32; 3]>("c") ?;
+            let ne = a.clone().ne(b.clone()).or(a.gt(c.clone()));
+            decl_sf.return_stmt(ne.and(b.ge(c).not()));
+            Ok(())
+          }) ?;
+          Ok(())
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_cmp = sh_cmp.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_cmp) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("fn in_range(x: f32, lo: f32, hi: f32) -> bool {"));
+      assert!(text.contains(
+        "return (((lo <= x) && (x < hi)) && (!((x == lo) || (x == hi))));"));
+      assert!(text.contains("-> vec3<bool> {"));
+      assert!(text.contains(
+        "return (((a != b) | (a > c)) & (!(b >= c)));"));
+    });
+}
+
+#[test]
+fn test_bool_buffer_rejected() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      for dims in 1 ..= 2 {
+        let result = pewter.declare(|decl| {
+          decl.shader_file("flags", |decl_sh| {
+            if dims == 1 {
+              decl_sh.use_buf::<bool>("flags") ?;
+            } else {
+              decl_sh.use_uniform::<[bool; 2]>("flags") ?;
+            }
+            Ok(())
+          }) ?;
+          Ok(())
+        });
+        match result {
+          Err(PewterError::NonHostShareableBuffer { buffer, .. }) =>
+            assert_eq!(buffer.as_ref(), "flags"),
+          _ => panic!("Expected a non-host-shareable buffer error"),
+        }
+      }
+    });
+}
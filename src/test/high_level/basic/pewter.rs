@@ -1,5 +1,6 @@
 
-use crate::{ Pewter, PewterConfig };
+use std::sync::Arc;
+use crate::{ Pewter, PewterConfig, PewterError };
 
 #[test]
 fn test_pewter_basics() {
@@ -13,20 +14,144 @@ fn test_pewter_basics() {
         .expect("Failed to create pewter.");
       pewter.declare(move |decl| {
         let sh_add_u32 = decl.shader_file("add_u32", |decl_sh| {
+          let lhs = decl_sh.use_buf::<u32>("lhs") ?;
+          decl_sh.use_buf::<f32>("rhs") ?;
 
           decl_sh.function("add_u32_u32", |decl_sf| {
-            let arg0 = decl_sf.use_arg::<u32>("arg0");
-            let arg1 = decl_sf.use_arg::<u32>("arg1");
+            let arg0 = decl_sf.use_arg::<u32>("arg0") ?;
+            let arg1 = decl_sf.use_arg::<u32>("arg1") ?;
             decl_sf.return_stmt(arg0 + arg1);
-          });
+            Ok(())
+          }) ?;
 
-          decl_sh.entry_function_1d("mul_coord_by_3", |decl_sh| {
-          });
-        });
+          decl_sh.entry_function_1d("mul_coord_by_3", |decl_sf| {
+            let coord = decl_sf.get_arg();
+            let value = coord.clone() + coord.clone() + coord.clone();
+            decl_sf.store(&lhs, coord, value);
+            Ok(())
+          }) ?;
+          Ok(())
+        }) ?;
 
-        let text = decl.shader_file_text(&sh_add_u32);
+        let text = decl.shader_file_text(&sh_add_u32) ?;
         eprint!("TEXT:\n{}", text);
+        assert!(text.contains(
+          "@group(0) @binding(0)\nvar<storage, read_write> lhs: array<u32>;"));
+        assert!(text.contains(
+          "@group(0) @binding(1)\nvar<storage, read_write> rhs: array<f32>;"));
+        assert!(text.contains(
+          "@group(1) @binding(0)\nvar<uniform> x_grid_size: vec3<u32>;"));
+        assert!(text.contains(
+          "lhs[global_id] = ((global_id + global_id) + global_id);"));
         Ok(())
       }).expect("Failed to declare resources.");
     });
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pewter_config() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default()
+        .backends(wgpu::Backends::all())
+        .power_preference(wgpu::PowerPreference::LowPower)
+        .limits(wgpu::Limits::downlevel_defaults());
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+      assert!(pewter.device().limits().max_storage_buffers_per_shader_stage
+        >= wgpu::Limits::downlevel_defaults()
+             .max_storage_buffers_per_shader_stage);
+
+      // Limits no adapter can satisfy are reported as errors.
+      let config = PewterConfig::default()
+        .limits(wgpu::Limits {
+          max_compute_workgroups_per_dimension: u32::MAX,
+          ..wgpu::Limits::downlevel_defaults()
+        });
+      let result = Pewter::new(config).await;
+      assert!(matches!(result,
+        Err(PewterError::UnsupportedLimit {
+          name: "max_compute_workgroups_per_dimension", ..
+        })));
+
+      // A pewter can share the device of another.
+      let config = PewterConfig::default()
+        .shared_device(pewter.device().clone(), pewter.queue().clone());
+      let shared = Pewter::new(config).await
+        .expect("Failed to create pewter from shared device.");
+      assert!(Arc::ptr_eq(shared.device(), pewter.device()));
+
+      // The shared device must still support the required features.
+      let config = PewterConfig::default()
+        .features(wgpu::Features::SHADER_FLOAT64)
+        .shared_device(pewter.device().clone(), pewter.queue().clone());
+      let result = Pewter::new(config).await;
+      assert!(matches!(result, Err(PewterError::UnsupportedFeatures(_))));
+    });
+}
+
+#[test]
+fn test_declare_errors() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("dup_arg", |decl_sh| {
+          decl_sh.function("f", |decl_sf| {
+            let arg0 = decl_sf.use_arg::<u32>("arg")?;
+            let arg1 = decl_sf.use_arg::<u32>("arg")?;
+            decl_sf.return_stmt(arg0 + arg1);
+            Ok(())
+          }) ?;
+          Ok(())
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::ArgumentNameCollision { .. })));
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("dup_buf", |decl_sh| {
+          decl_sh.use_buf::<u32>("data") ?;
+          decl_sh.use_buf::<f32>("data") ?;
+          Ok(())
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::BufferNameCollision { .. })));
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("dup_func", |decl_sh| {
+          decl_sh.entry_function_1d("main", |_| Ok(())) ?;
+          decl_sh.entry_function_2d("main", |_| Ok(())) ?;
+          Ok(())
+        }) ?;
+        Ok(())
+      });
+      assert!(matches!(result,
+        Err(PewterError::FunctionNameCollision { .. })));
+
+      let result = pewter.declare(|decl| {
+        decl.shader_file("dup_file", |_| Ok(())) ?;
+        decl.shader_file("dup_file", |_| Ok(())) ?;
+        Ok(())
+      });
+      match result {
+        Err(err @ PewterError::ShaderFileNameCollision(_)) => {
+          assert_eq!(err.to_string(),
+            "Shader file dup_file is already declared");
+        },
+        _ => panic!("Expected shader file name collision"),
+      }
+    });
+}
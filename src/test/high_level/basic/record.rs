@@ -0,0 +1,82 @@
+use crate::{ Pewter, PewterConfig, DataType, RecordType };
+
+#[test]
+fn test_record_structs() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_copy = None;
+      pewter.declare(|decl| {
+        sh_copy = Some(decl.shader_file("copy_links", |decl_sh| {
+          let input = decl_sh.use_buf_read::<Link>("input") ?;
+          let output = decl_sh.use_buf::<Link>("output") ?;
+          decl_sh.function::<Body, _>("identity", |decl_sf| {
+            let body = decl_sf.use_arg::<Body>("body") ?;
+            decl_sf.return_stmt(body);
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_1d("copy_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            decl_sf.store(&output, id.clone(), input.load(id));
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_copy = sh_copy.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_copy) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(!text.contains("::"));
+      let body_pos = text.find("_Body {\n  id: u32,\n  pos: vec3<f32>,\n}")
+        .expect("Missing struct for Body.");
+      let link_pos = text.find("_Link {\n  head: ")
+        .expect("Missing struct for Link.");
+      assert!(body_pos < link_pos);
+      assert_eq!(text.matches("_Body {\n  id").count(), 1);
+
+      let links = (0 .. 8_u32).map(|i| Link {
+        head: Body { id: i, pos: [i as f32, 1.0, 2.0] },
+        tail: Body { id: i + 1, pos: [0.5, i as f32, -1.0] },
+        weight: i as f32 * 0.25,
+      }).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let in_buf = cons.buffer::<Link>("input", links.len()) ?;
+        let out_buf = cons.buffer::<Link>("output", links.len()) ?;
+        cons.write_buffer(&in_buf, &links) ?;
+        cons.dispatch(&sh_copy, "copy_entry", &[links.len() as u32]) ?
+          .bind("input", &in_buf) ?
+          .bind("output", &out_buf) ?;
+        let readback = cons.read_buffer(&out_buf);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      assert_eq!(output, links);
+    });
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Body {
+  id: u32,
+  pos: [f32; 3],
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Link {
+  head: Body,
+  tail: Body,
+  weight: f32,
+}
@@ -0,0 +1,181 @@
+use crate::{ Pewter, PewterConfig, PewterError, BufferShape, lit, vec3 };
+
+#[test]
+fn test_buffer_shapes() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_grid = None;
+      pewter.declare(|decl| {
+        sh_grid = Some(decl.shader_file("grid", |decl_sh| {
+          let cells = decl_sh.use_buf_2d::<f32>("cells") ?;
+          let row = decl_sh.use_buf::<u32>("row") ?;
+          decl_sh.function::<u32, _>("cell_index", |decl_sf| {
+            let coord = decl_sf.use_arg::<[u32; 2]>("coord") ?;
+            decl_sf.return_stmt(cells.linear_index(coord) ?);
+            Ok(())
+          }) ?;
+          decl_sh.function::<u32, _>("row_index", |decl_sf| {
+            let coord = decl_sf.use_arg::<[u32; 2]>("coord") ?;
+            let result = row.linear_index(coord);
+            assert!(matches!(result,
+              Err(PewterError::BufferDimensionMismatch {
+                expected: 1, actual: 2, ..
+              })));
+            let coord = decl_sf.use_arg::<u32>("i") ?;
+            decl_sf.return_stmt(row.linear_index(coord) ?);
+            Ok(())
+          }) ?;
+          decl_sh.entry_function_2d("noop_entry", |_decl_sf| Ok(()))
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_grid = sh_grid.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_grid) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains(
+        "var<uniform> x_buffer_shapes: array<vec4<u32>, 2>;"));
+      assert!(text.contains(
+        "fn x_index_cells(coord: vec2<u32>) -> u32 {"));
+      assert!(text.contains("x_index_cells(coord)"));
+      assert!(!text.contains("fn x_index_row"));
+
+      let shape = BufferShape::new_2d(5, 3);
+      assert_eq!(shape.len(), 15);
+      assert_eq!(shape.extents(), &[5, 3]);
+      let input = (0 .. 15).map(|i| i as f32 * 0.5).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let cells = cons.shaped_buffer::<f32>("cells", shape) ?;
+        let row = cons.buffer::<u32>("row", 4) ?;
+        cons.write_buffer(&cells, &input) ?;
+        cons.dispatch(&sh_grid, "noop_entry", &[5, 3]) ?
+          .bind("cells", &cells) ?
+          .bind("row", &row) ?;
+        let readback = cons.read_buffer(&cells);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      assert_eq!(output, input);
+
+      // Buffers must have as many dimensions as their slot.
+      let result = pewter.construct::<(), _>(|cons| {
+        let cells = cons.buffer::<f32>("cells", 15) ?;
+        cons.dispatch(&sh_grid, "noop_entry", &[5, 3]) ?
+          .bind("cells", &cells) ?;
+        Ok(())
+      });
+      assert!(matches!(result, Err(PewterError::BufferShapeMismatch {
+        expected: 2, actual: 1, ..
+      })));
+    });
+}
+
+#[test]
+fn test_linear_index_on_gpu() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_2d = None;
+      let mut sh_3d = None;
+      pewter.declare(|decl| {
+        sh_2d = Some(decl.shader_file("fill_2d", |decl_sh| {
+          let src = decl_sh.use_buf_read_2d::<u32>("src") ?;
+          let dst = decl_sh.use_buf_2d::<u32>("dst") ?;
+          decl_sh.entry_function_2d("fill_2d_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let value = src.load(src.linear_index(id.clone()) ?)
+              + id.clone().x() * lit(1000)
+              + id.clone().y() * lit(100000);
+            decl_sf.store(&dst, dst.linear_index(id) ?, value);
+            Ok(())
+          })
+        }) ?);
+        // The coordinate is unpacked from a linear id with the
+        // shape's extents, 4 by 3 by 2.
+        sh_3d = Some(decl.shader_file("fill_3d", |decl_sh| {
+          let src = decl_sh.use_buf_read_3d::<u32>("src") ?;
+          let dst = decl_sh.use_buf_3d::<u32>("dst") ?;
+          decl_sh.entry_function_1d("fill_3d_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let x = decl_sf.let_("x", id.clone() % lit(4));
+            let y = decl_sf.let_("y", (id.clone() / lit(4)) % lit(3));
+            let z = decl_sf.let_("z", id / lit(12));
+            let coord = decl_sf.let_("coord",
+              vec3(x.clone(), y.clone(), z.clone()));
+            let value = src.load(src.linear_index(coord.clone()) ?)
+              + x + y * lit(10) + z * lit(100);
+            decl_sf.store(&dst, dst.linear_index(coord) ?, value);
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_2d = sh_2d.unwrap();
+      let sh_3d = sh_3d.unwrap();
+
+      // A non-square shape, so that swapped strides are caught.
+      let shape = BufferShape::new_2d(5, 3);
+      let input = (0 .. 15_u32).map(|i| i * 7).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let src = cons.shaped_buffer::<u32>("src", shape) ?;
+        let dst = cons.shaped_buffer::<u32>("dst", shape) ?;
+        cons.write_buffer(&src, &input) ?;
+        cons.dispatch(&sh_2d, "fill_2d_entry", &[5, 3]) ?
+          .bind("src", &src) ?
+          .bind("dst", &dst) ?;
+        let readback = cons.read_buffer(&dst);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      for y in 0 .. 3 {
+        for x in 0 .. 5 {
+          let i = (y * 5 + x) as usize;
+          assert_eq!(output[i], input[i] + x * 1000 + y * 100000);
+        }
+      }
+
+      let shape = BufferShape::new_3d(4, 3, 2);
+      let input = (0 .. 24_u32).map(|i| i * 3).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let src = cons.shaped_buffer::<u32>("src", shape) ?;
+        let dst = cons.shaped_buffer::<u32>("dst", shape) ?;
+        cons.write_buffer(&src, &input) ?;
+        cons.dispatch(&sh_3d, "fill_3d_entry", &[24]) ?
+          .bind("src", &src) ?
+          .bind("dst", &dst) ?;
+        let readback = cons.read_buffer(&dst);
+        cons.result(move |out| out.get(readback));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let output = construct.perform().await
+        .expect("Failed to perform construct.");
+      for z in 0 .. 2 {
+        for y in 0 .. 3 {
+          for x in 0 .. 4 {
+            let i = ((z * 3 + y) * 4 + x) as usize;
+            assert_eq!(output[i], input[i] + x + y * 10 + z * 100);
+          }
+        }
+      }
+    });
+}
@@ -0,0 +1,95 @@
+use crate::{ Pewter, PewterConfig, lit, vec2, vec3, vec4, X, Y, Z };
+
+#[test]
+fn test_swizzles() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_swz = None;
+      pewter.declare(|decl| {
+        sh_swz = Some(decl.shader_file("swz", |decl_sh| {
+          let pos = decl_sh.use_buf::<[f32; 3]>("pos") ?;
+          let quads = decl_sh.use_buf::<[f32; 4]>("quads") ?;
+          let pairs = decl_sh.use_buf::<[i32; 2]>("pairs") ?;
+          let words = decl_sh.use_buf::<u32>("words") ?;
+          decl_sh.entry_function_1d("swz_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let p = decl_sf.let_("p", pos.load(id.clone()));
+            decl_sf.store(&pos, id.clone(), vec3(
+              p.clone().z(),
+              p.clone().x() + p.clone().y(),
+              lit(1.0)));
+            decl_sf.store(&quads, id.clone(),
+              p.clone().swizzle((Z, Z, Y, X)) + vec4(
+                p.clone().xy().y(), lit(0.0), lit(0.0), lit(0.0)));
+            let q = pairs.load(id.clone()).swizzle((Y, X));
+            decl_sf.store(&pairs, id.clone(), q * lit(2));
+            let v = vec4(id.clone(), lit(7_u32), id.clone() + lit(1),
+              id.clone() * lit(2));
+            decl_sf.store(&words, id.clone(),
+              v.w() + vec2(id, lit(3)).y());
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_swz = sh_swz.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_swz) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("vec3<f32>(p.z, (p.x + p.y), 1.0f)"));
+      assert!(text.contains("(p.zzyx + vec4<f32>(p.xy.y, 0.0f, 0.0f, 0.0f))"));
+      assert!(text.contains("(pairs[global_id].yx * 2i)"));
+
+      let n = 16_usize;
+      let pos_in = (0 .. n)
+        .map(|i| [i as f32, 0.5 - i as f32, i as f32 * 3.0])
+        .collect::<Vec<_>>();
+      let pairs_in = (0 .. n as i32)
+        .map(|i| [i, -i * 5])
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let pos = cons.buffer::<[f32; 3]>("pos", n) ?;
+        let quads = cons.buffer::<[f32; 4]>("quads", n) ?;
+        let pairs = cons.buffer::<[i32; 2]>("pairs", n) ?;
+        let words = cons.buffer::<u32>("words", n) ?;
+        cons.write_buffer(&pos, &pos_in) ?;
+        cons.write_buffer(&pairs, &pairs_in) ?;
+        cons.dispatch(&sh_swz, "swz_entry", &[n as u32]) ?
+          .bind("pos", &pos) ?
+          .bind("quads", &quads) ?
+          .bind("pairs", &pairs) ?
+          .bind("words", &words) ?;
+        let pos_back = cons.read_buffer(&pos);
+        let quads_back = cons.read_buffer(&quads);
+        let pairs_back = cons.read_buffer(&pairs);
+        let words_back = cons.read_buffer(&words);
+        cons.result(move |out| {
+          (out.get(pos_back), out.get(quads_back),
+           out.get(pairs_back), out.get(words_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let (pos_out, quads_out, pairs_out, words_out) = construct.perform()
+        .await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let [x, y, z] = pos_in[i];
+        assert_eq!(pos_out[i], [z, x + y, 1.0]);
+        assert_eq!(quads_out[i], [z + y, z, y, x]);
+        let [a, b] = pairs_in[i];
+        assert_eq!(pairs_out[i], [b * 2, a * 2]);
+        assert_eq!(words_out[i], i as u32 * 2 + 3);
+      }
+    });
+}
@@ -0,0 +1,135 @@
+use crate::{
+  Pewter,
+  PewterConfig,
+  PewterError,
+  DataType,
+  RecordType,
+  lit,
+};
+
+#[test]
+fn test_tail_buffers() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_tail = None;
+      pewter.declare(|decl| {
+        sh_tail = Some(decl.shader_file("tail", |decl_sh| {
+          let src =
+            decl_sh.use_tail_buf_read::<Header, [f32; 4]>("src") ?;
+          let dst = decl_sh.use_tail_buf::<Header, [f32; 4]>("dst") ?;
+          let lens = decl_sh.use_buf::<u32>("lens") ?;
+          decl_sh.entry_function_1d("tail_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            decl_sf.store_tail(&dst, id.clone(),
+              src.load(id.clone()) * lit(2.0_f32));
+            decl_sf.if_(id.clone().eq(lit(0)), |body| {
+              body.store_header(&dst, src.load_header());
+              Ok(())
+            }) ?;
+            decl_sf.store(&lens, id, src.array_length());
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_tail = sh_tail.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_tail) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("struct x_tail_src {\n  header: "));
+      assert!(text.contains("_Header,\n  items: array<vec4<f32>>,\n}"));
+      assert!(text.contains("var<storage, read> src: x_tail_src;"));
+      assert!(text.contains("var<storage, read_write> dst: x_tail_dst;"));
+      assert!(text.contains("dst.header = src.header;"));
+      assert!(text.contains("arrayLength((&src.items))"));
+
+      // The header takes 8 bytes, and the array follows at the
+      // 16-byte alignment of its elements.
+      let n = 8_usize;
+      let header = Header { count: 5, scale: 0.25 };
+      let items_in = (0 .. 5)
+        .map(|i| [i as f32, i as f32 + 0.5, -(i as f32), 1.0])
+        .collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let src = cons.tail_buffer::<Header, [f32; 4]>("src", n) ?;
+        let dst = cons.tail_buffer::<Header, [f32; 4]>("dst", n) ?;
+        let lens = cons.buffer::<u32>("lens", n) ?;
+        cons.write_tail_buffer(&src, &header, &items_in) ?;
+        cons.dispatch(&sh_tail, "tail_entry", &[n as u32]) ?
+          .bind_tail("src", &src) ?
+          .bind_tail("dst", &dst) ?
+          .bind("lens", &lens) ?;
+        let src_back = cons.read_tail_buffer(&src);
+        let dst_back = cons.read_tail_buffer(&dst);
+        let lens_back = cons.read_buffer(&lens);
+        cons.result(move |out| {
+          (out.get_tail(src_back), out.get_tail(dst_back), out.get(lens_back))
+        });
+        Ok(())
+      }).expect("Failed to make construct.");
+      let ((src_header, src_out), (dst_header, dst_out), lens_out) =
+        construct.perform().await
+          .expect("Failed to perform construct.");
+
+      assert_eq!(src_header, header);
+      assert_eq!(dst_header, header);
+      for i in 0 .. n {
+        let expected = items_in.get(i).copied().unwrap_or_default();
+        assert_eq!(src_out[i], expected);
+        assert_eq!(dst_out[i], expected.map(|x| x * 2.0));
+        assert_eq!(lens_out[i], n as u32);
+      }
+
+      // An empty array is given one element, both on the host and
+      // on the gpu.
+      let construct = pewter.construct(|cons| {
+        let src = cons.tail_buffer::<Header, [f32; 4]>("src", 0) ?;
+        let dst = cons.tail_buffer::<Header, [f32; 4]>("dst", 0) ?;
+        let lens = cons.buffer::<u32>("lens", 1) ?;
+        assert_eq!(src.len(), 1);
+        cons.dispatch(&sh_tail, "tail_entry", &[1]) ?
+          .bind_tail("src", &src) ?
+          .bind_tail("dst", &dst) ?
+          .bind("lens", &lens) ?;
+        let lens_back = cons.read_buffer(&lens);
+        cons.result(move |out| out.get(lens_back));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let lens_out = construct.perform().await
+        .expect("Failed to perform construct.");
+      assert_eq!(lens_out, vec![1]);
+
+      // A plain buffer of the elements does not match a tail slot.
+      let result = pewter.construct(|cons| {
+        let src = cons.buffer::<[f32; 4]>("src", n) ?;
+        cons.dispatch(&sh_tail, "tail_entry", &[n as u32]) ?
+          .bind("src", &src) ?;
+        cons.result(|_| ());
+        Ok(())
+      });
+      match result {
+        Err(PewterError::BufferTypeMismatch { expected, actual, .. }) => {
+          assert!(expected.ends_with("::Header with array<vec4<f32>>"));
+          assert_eq!(actual, "vec4<f32>");
+        },
+        _ => panic!("Expected a buffer type mismatch"),
+      }
+    });
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Header {
+  count: u32,
+  scale: f32,
+}
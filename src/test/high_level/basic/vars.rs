@@ -0,0 +1,93 @@
+use crate::{ Pewter, PewterConfig };
+
+#[test]
+fn test_local_vars() {
+  tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .expect("Failed to build tokio runtime")
+    .block_on(async {
+      let config = PewterConfig::default();
+      let pewter = Pewter::new(config).await
+        .expect("Failed to create pewter.");
+
+      let mut sh_vars = None;
+      pewter.declare(|decl| {
+        sh_vars = Some(decl.shader_file("vars", |decl_sh| {
+          let vals = decl_sh.use_buf::<u32>("vals") ?;
+          let lo = decl_sh.use_buf_read::<u32>("lo") ?;
+          let hi = decl_sh.use_buf_read::<u32>("hi") ?;
+          decl_sh.entry_function_1d("vars_entry", |decl_sf| {
+            let id = decl_sf.get_arg();
+            let v = decl_sf.let_("v", vals.load(id.clone()));
+            let start = decl_sf.let_("start", lo.load(id.clone()));
+            let acc = decl_sf.var("acc", v.clone());
+            decl_sf.for_range(start.clone(), hi.load(id.clone()), |body, i| {
+              body.add_assign(&acc, i.clone());
+              body.xor_assign(&acc, v.clone() << i);
+              Ok(())
+            }) ?;
+            decl_sf.shr_assign(&acc, start.clone());
+            decl_sf.sub_assign(&acc, start);
+            // A second `v` is renamed rather than redeclared.
+            let v = decl_sf.let_("v", acc.get() * v);
+            decl_sf.assign(&acc, v.clone() + v);
+            decl_sf.store(&vals, id, acc.get());
+            Ok(())
+          })
+        }) ?);
+        Ok(())
+      }).expect("Failed to declare resources.");
+      let sh_vars = sh_vars.unwrap();
+
+      let mut text = String::new();
+      pewter.declare(|decl| {
+        text = decl.shader_file_text(&sh_vars) ?;
+        Ok(())
+      }).expect("Failed to get shader text.");
+      assert!(text.contains("  let v: u32 = vals[global_id];\n"));
+      assert!(text.contains("  var acc: u32 = v;\n"));
+      assert!(text.contains("    acc += x_counter;\n"));
+      assert!(text.contains("    acc ^= (v << x_counter);\n"));
+      assert!(text.contains("  acc >>= start;\n"));
+      assert!(text.contains("  let v_1: u32 = (acc * v);\n"));
+      assert!(text.contains("  acc = (v_1 + v_1);\n"));
+      assert!(text.contains("  vals[global_id] = acc;\n"));
+
+      let n = 20_usize;
+      let vals_in = (0 .. n as u32).map(|i| i * 37 + 5).collect::<Vec<_>>();
+      let lo_in = (0 .. n as u32).map(|i| i % 4).collect::<Vec<_>>();
+      let hi_in = (0 .. n as u32).map(|i| i % 4 + i % 6).collect::<Vec<_>>();
+      let construct = pewter.construct(|cons| {
+        let vals = cons.buffer::<u32>("vals", n) ?;
+        let lo = cons.buffer::<u32>("lo", n) ?;
+        let hi = cons.buffer::<u32>("hi", n) ?;
+        cons.write_buffer(&vals, &vals_in) ?;
+        cons.write_buffer(&lo, &lo_in) ?;
+        cons.write_buffer(&hi, &hi_in) ?;
+        cons.dispatch(&sh_vars, "vars_entry", &[n as u32]) ?
+          .bind("vals", &vals) ?
+          .bind("lo", &lo) ?
+          .bind("hi", &hi) ?;
+        let vals_back = cons.read_buffer(&vals);
+        cons.result(move |out| out.get(vals_back));
+        Ok(())
+      }).expect("Failed to make construct.");
+      let vals_out = construct.perform().await
+        .expect("Failed to perform construct.");
+
+      for i in 0 .. n {
+        let (v, start) = (vals_in[i], lo_in[i]);
+        let mut acc = v;
+        for x in start .. hi_in[i] {
+          acc = acc.wrapping_add(x);
+          acc ^= v << x;
+        }
+        acc >>= start;
+        acc = acc.wrapping_sub(start);
+        let v = acc.wrapping_mul(v);
+        acc = v.wrapping_add(v);
+        assert_eq!(vals_out[i], acc, "Unexpected value for {}", i);
+      }
+    });
+}
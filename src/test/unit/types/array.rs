@@ -0,0 +1,90 @@
+use crate::{
+  DataType,
+  RecordType,
+  types::{
+    FixedArray,
+    data_type_info_for,
+    data_type_read,
+    data_type_write,
+  },
+};
+
+#[test]
+fn test_array_layout() {
+  let floats = data_type_info_for::<FixedArray<f32, 16>>();
+  assert_eq!(floats.name(), "array<f32, 16>");
+  assert_eq!(floats.size(), 64);
+  assert_eq!(floats.align(), 4);
+
+  // Elements are padded to their alignment.
+  let vectors = data_type_info_for::<FixedArray<[f32; 3], 4>>();
+  assert_eq!(vectors.size(), 64);
+  assert_eq!(vectors.align(), 16);
+
+  let spans = data_type_info_for::<FixedArray<Span, 3>>();
+  assert_eq!(spans.size(), 36);
+  assert_eq!(spans.align(), 4);
+}
+
+#[test]
+fn test_array_values() {
+  let value = FixedArray([[1_u32, 2, 3], [4, 5, 6]]);
+  let mut bytes = vec![0xff_u8; 32];
+  data_type_write(&value, &mut bytes);
+  assert_eq!(&bytes[16 .. 20], &4_u32.to_le_bytes());
+  assert_eq!(&bytes[12 .. 16], &[0xff; 4]);
+
+  let mut value2 = FixedArray::default();
+  data_type_read(&mut value2, &bytes);
+  assert_eq!(value, value2);
+}
+
+#[test]
+fn test_array_uniform_layout() {
+  let floats = data_type_info_for::<FixedArray<f32, 4>>();
+  assert_eq!(floats.uniform_layout_violation().as_ref().map(|n| n.as_ref()),
+    Some("array<f32, 4>"));
+  let vectors = data_type_info_for::<FixedArray<[f32; 4], 2>>();
+  assert_eq!(vectors.uniform_layout_violation(), None);
+  let samples = data_type_info_for::<Samples>();
+  assert_eq!(samples.uniform_layout_violation().as_ref().map(|n| n.as_ref()),
+    Some("weights"));
+}
+
+#[test]
+fn test_derived_array_fields() {
+  let data_type = data_type_info_for::<Samples>();
+  // `spans` follows the 32 bytes of `weights` directly.
+  assert_eq!(data_type.size(), 68);
+
+  let samples = Samples {
+    weights: FixedArray([0.5, 1.5, -2.0, 4.0, 8.0, 0.0, 1.0, 2.0]),
+    spans: FixedArray([
+      Span { start: 0, len: 4, step: 1 },
+      Span { start: 4, len: 2, step: 2 },
+      Span { start: 9, len: 1, step: 3 },
+    ]),
+  };
+  let mut bytes = vec![0_u8; data_type.size()];
+  data_type_write(&samples, &mut bytes);
+  assert_eq!(&bytes[12 .. 16], &4.0_f32.to_le_bytes());
+  assert_eq!(&bytes[44 .. 48], &4_u32.to_le_bytes());
+  let mut samples2 = Samples::default();
+  data_type_read(&mut samples2, &bytes);
+  assert_eq!(samples, samples2);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Span {
+  start: u32,
+  len: u32,
+  step: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Samples {
+  weights: FixedArray<f32, 8>,
+  spans: FixedArray<Span, 3>,
+}
@@ -0,0 +1,36 @@
+use crate::{
+  types::{
+    Atomic,
+    AtomicTypeInfo,
+    PrimType,
+    PrimTypeInfo,
+    data_type_info_for,
+  },
+};
+
+#[test]
+fn test_atomic_info() {
+  let info = <Atomic<u32> as PrimType>::PRIM_INFO;
+  assert_eq!(info, PrimTypeInfo::Atomic(AtomicTypeInfo::U32));
+  assert_eq!(info.name(), "atomic<u32>");
+  assert_eq!(info.size(), 4);
+  assert_eq!(info.align(), 4);
+
+  let data_type = data_type_info_for::<Atomic<i32>>();
+  assert_eq!(data_type.name(), "atomic<i32>");
+  assert_eq!(data_type.array_stride(), 4);
+  assert!(data_type.is_host_shareable());
+  assert!(data_type.has_atomics());
+  assert!(!data_type_info_for::<i32>().has_atomics());
+}
+
+#[test]
+fn test_atomic_bytes() {
+  // Atomics are written and read as the scalar they hold.
+  let mut buf: [u8; 4] = Default::default();
+  let mut read_value = Atomic::default();
+  Atomic(-99_i32).write_bytes(&mut buf);
+  assert_eq!(buf, (-99_i32).to_le_bytes());
+  read_value.read_bytes(&buf);
+  assert_eq!(read_value, Atomic(-99_i32));
+}
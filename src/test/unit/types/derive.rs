@@ -0,0 +1,126 @@
+use crate::{
+  DataType,
+  RecordType,
+  types::{record_info_for_type, data_type_info_for},
+};
+
+#[test]
+fn test_derived_record() {
+  let record_info = record_info_for_type::<Rect>();
+  let rect = Rect {
+    top_left: Point { x: 99, y: 33 },
+    size: [101, 7],
+  };
+  let mut bytes = vec![0_u8; record_info.size()];
+  record_info.write_value(&rect, &mut bytes);
+  let mut rect2 = Rect::default();
+  record_info.read_value(&mut rect2, &bytes);
+  assert_eq!(rect, rect2);
+  assert_eq!(bytes, [
+    99, 0, 0, 0,
+    33, 0, 0, 0,
+    101, 0, 0, 0,
+    7, 0, 0, 0,
+  ]);
+}
+
+#[test]
+fn test_derived_vector_fields() {
+  let data_type = data_type_info_for::<Particle>();
+  // `pos` is aligned to 16 bytes, and `bounds` follows it at 32.
+  assert_eq!(data_type.size(), 48);
+  assert_eq!(data_type.align(), 16);
+
+  let record_info = record_info_for_type::<Particle>();
+  let particle = Particle {
+    id: 3,
+    pos: [1.0, -2.0, 0.5],
+    bounds: Rect { top_left: Point { x: 1, y: 2 }, size: [3, 4] },
+  };
+  let mut bytes = vec![0_u8; record_info.size()];
+  record_info.write_value(&particle, &mut bytes);
+  let mut particle2 = Particle::default();
+  record_info.read_value(&mut particle2, &bytes);
+  assert_eq!(particle, particle2);
+  assert_eq!(&bytes[16 .. 20], &1.0_f32.to_le_bytes());
+  assert_eq!(&bytes[32 .. 36], &1_u32.to_le_bytes());
+}
+
+#[test]
+fn test_derived_matrix_fields() {
+  let data_type = data_type_info_for::<Transform>();
+  // `rotation` is three 16 byte columns, and `scale` follows it.
+  assert_eq!(data_type.size(), 64);
+  assert_eq!(data_type.align(), 16);
+
+  let record_info = record_info_for_type::<Transform>();
+  let transform = Transform {
+    rotation: [[0.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+    scale: 2.5,
+  };
+  let mut bytes = vec![0_u8; record_info.size()];
+  record_info.write_value(&transform, &mut bytes);
+  let mut transform2 = Transform::default();
+  record_info.read_value(&mut transform2, &bytes);
+  assert_eq!(transform, transform2);
+  assert_eq!(&bytes[16 .. 20], &(-1.0_f32).to_le_bytes());
+  assert_eq!(&bytes[48 .. 52], &2.5_f32.to_le_bytes());
+}
+
+#[test]
+fn test_record_wgsl_names_unique() {
+  // Both type names become `..._names_a_b_c_D` as identifiers.
+  let first = record_info_for_type::<names::a::b_c::D>();
+  let second = record_info_for_type::<names::a::b::c_D>();
+  assert!(first.wgsl_name().ends_with("names_a_b_c_D"));
+  assert!(second.wgsl_name().contains("names_a_b_c_D"));
+  assert_ne!(first.wgsl_name(), second.wgsl_name());
+}
+
+mod names {
+  pub mod a {
+    pub mod b_c {
+      #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+      #[derive(crate::DataType, crate::RecordType)]
+      #[pewter(crate = "crate")]
+      pub struct D { pub x: u32 }
+    }
+    pub mod b {
+      // Named to collide with `b_c::D` once flattened.
+      #[allow(non_camel_case_types)]
+      #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+      #[derive(crate::DataType, crate::RecordType)]
+      #[pewter(crate = "crate")]
+      pub struct c_D { pub x: u32 }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Point {
+  x: u32,
+  y: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Rect {
+  top_left: Point,
+  size: [u32; 2],
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Particle {
+  id: u32,
+  pos: [f32; 3],
+  bounds: Rect,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, DataType, RecordType)]
+#[pewter(crate = "crate")]
+struct Transform {
+  rotation: [[f32; 3]; 3],
+  scale: f32,
+}
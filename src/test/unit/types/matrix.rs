@@ -0,0 +1,64 @@
+use crate::{
+  types::{
+    PrimType,
+    MatrixType,
+    MatrixTypeInfo,
+  },
+};
+
+#[test]
+fn test_matrix_layout() {
+  // Columns are padded to the alignment of their vector type.
+  let layouts = [
+    (MatrixTypeInfo::Mat2x2, 16, 8),
+    (MatrixTypeInfo::Mat2x3, 32, 16),
+    (MatrixTypeInfo::Mat2x4, 32, 16),
+    (MatrixTypeInfo::Mat3x2, 24, 8),
+    (MatrixTypeInfo::Mat3x3, 48, 16),
+    (MatrixTypeInfo::Mat3x4, 48, 16),
+    (MatrixTypeInfo::Mat4x2, 32, 8),
+    (MatrixTypeInfo::Mat4x3, 64, 16),
+    (MatrixTypeInfo::Mat4x4, 64, 16),
+  ];
+  for (info, size, align) in layouts {
+    assert_eq!(info.size(), size, "size of {}", info.name());
+    assert_eq!(info.align(), align, "align of {}", info.name());
+  }
+  assert_eq!(<[[f32; 3]; 4]>::MATRIX_INFO, MatrixTypeInfo::Mat4x3);
+  assert_eq!(<[[f32; 3]; 4]>::NAME, "mat4x3<f32>");
+}
+
+#[test]
+fn test_matrix_4x3() {
+  let value = [
+    [1.0_f32, 2.0, 3.0],
+    [4.0, 5.0, 6.0],
+    [-7.5, 8.25, 9.0],
+    [f32::MAX, f32::MIN, 0.0],
+  ];
+  let mut buf = [0xff_u8; 64];
+  value.write_bytes(&mut buf);
+  // Each column starts 16 bytes after the previous, leaving the
+  // padding untouched.
+  assert_eq!(&buf[16 .. 20], &4.0_f32.to_le_bytes());
+  assert_eq!(&buf[12 .. 16], &[0xff; 4]);
+  assert_eq!(&buf[32 .. 36], &(-7.5_f32).to_le_bytes());
+
+  let mut read_value = [[0.0_f32; 3]; 4];
+  read_value.read_bytes(&buf);
+  assert_eq!(read_value, value);
+}
+
+#[test]
+fn test_matrix_3x2() {
+  let value = [[1.0_f32, -2.0], [0.5, 1e-7], [3.0, 4.0]];
+  let mut buf = [0_u8; 24];
+  value.write_bytes(&mut buf);
+  // Two row columns are packed without padding.
+  assert_eq!(&buf[8 .. 12], &0.5_f32.to_le_bytes());
+  assert_eq!(&buf[20 .. 24], &4.0_f32.to_le_bytes());
+
+  let mut read_value = [[0.0_f32; 2]; 3];
+  read_value.read_bytes(&buf);
+  assert_eq!(read_value, value);
+}
@@ -1,4 +1,14 @@
 
+// These tests check the extreme values through `max_value()` and
+// `min_value()`, and an f32 constant exactly as written.
+#[allow(clippy::legacy_numeric_constants, clippy::excessive_precision)]
 mod scalar;
+#[allow(clippy::legacy_numeric_constants)]
 mod vector;
-mod record;
\ No newline at end of file
+mod matrix;
+mod array;
+mod atomic;
+// Compares byte slices by reference.
+#[allow(clippy::op_ref)]
+mod record;
+mod derive;
@@ -6,6 +6,7 @@ use crate::types::{
   DataTypeSpecializeMut,
   RecordTypeFieldSpecifier,
   record_info_for_type,
+  data_type_info_for,
 };
 
 #[test]
@@ -49,6 +50,16 @@ fn test_rectangle_record() {
     "Unexpected bytes: {:?}", &bytes);
 }
 
+#[test]
+fn test_uniform_layout() {
+  assert_eq!(data_type_info_for::<u32>().uniform_layout_violation(), None);
+  assert_eq!(data_type_info_for::<Point>().uniform_layout_violation(), None);
+  // `size` follows the 8-byte `top_left` directly, where the uniform
+  // address space needs 16 bytes between them.
+  let violation = data_type_info_for::<Rect>().uniform_layout_violation();
+  assert_eq!(violation.as_ref().map(|n| n.as_ref()), Some("size"));
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 struct Point {
   x: u32,
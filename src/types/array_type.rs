@@ -0,0 +1,108 @@
+use std::ops::{Deref, DerefMut};
+use super::{
+  DataType,
+  DataTypeInfo,
+  DataTypeSpecialize,
+  DataTypeSpecializeRef,
+  DataTypeSpecializeMut,
+  NonAtomic,
+  SharedName,
+  data_type_info_for,
+  intern_name,
+};
+
+/**
+ * Rust type that identifies to a WGSL fixed-size array type,
+ * `array<T, N>`.
+ */
+pub trait ArrayType: DataType {
+  type Element: DataType;
+  const LEN: usize;
+  fn elements(&self) -> &[Self::Element];
+  fn elements_mut(&mut self) -> &mut [Self::Element];
+}
+
+/**
+ * An array of `N` values of any data type.  Rust arrays of 2 to 4
+ * scalars are already vectors, so arrays are held in this wrapper:
+ * `FixedArray<f32, 16>` is `array<f32, 16>`.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FixedArray<DT: DataType, const N: usize>(pub [DT; N]);
+
+impl<DT: DataType, const N: usize> FixedArray<DT, N> {
+  pub fn new(elements: [DT; N]) -> Self { FixedArray(elements) }
+  pub fn into_inner(self) -> [DT; N] { self.0 }
+}
+impl<DT: DataType, const N: usize> Default for FixedArray<DT, N> {
+  fn default() -> Self { FixedArray([DT::default(); N]) }
+}
+impl<DT: DataType, const N: usize> From<[DT; N]> for FixedArray<DT, N> {
+  fn from(elements: [DT; N]) -> Self { FixedArray(elements) }
+}
+impl<DT: DataType, const N: usize> Deref for FixedArray<DT, N> {
+  type Target = [DT; N];
+  fn deref(&self) -> &[DT; N] { &self.0 }
+}
+impl<DT: DataType, const N: usize> DerefMut for FixedArray<DT, N> {
+  fn deref_mut(&mut self) -> &mut [DT; N] { &mut self.0 }
+}
+
+impl<DT: DataType, const N: usize> DataType for FixedArray<DT, N> {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.array::<Self>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.array(self)
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.array(self)
+  }
+}
+impl<DT: NonAtomic, const N: usize> NonAtomic for FixedArray<DT, N> {}
+impl<DT: DataType, const N: usize> ArrayType for FixedArray<DT, N> {
+  type Element = DT;
+  const LEN: usize = N;
+  fn elements(&self) -> &[DT] { &self.0 }
+  fn elements_mut(&mut self) -> &mut [DT] { &mut self.0 }
+}
+
+/**
+ * Runtime type-information for `ArrayType`.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArrayTypeInfo {
+  element: Box<DataTypeInfo>,
+  num_elements: usize,
+  name: SharedName,
+  wgsl_name: SharedName,
+}
+impl ArrayTypeInfo {
+  pub(crate) fn for_type<AT: ArrayType>() -> Self {
+    let element = data_type_info_for::<AT::Element>();
+    let name = intern_name(
+      &format!("array<{}, {}>", element.name(), AT::LEN));
+    let wgsl_name = intern_name(
+      &format!("array<{}, {}>", element.wgsl_name(), AT::LEN));
+    ArrayTypeInfo {
+      element: Box::new(element),
+      num_elements: AT::LEN,
+      name,
+      wgsl_name,
+    }
+  }
+  pub(crate) fn into_data_type(self) -> DataTypeInfo {
+    DataTypeInfo::Array(self)
+  }
+
+  /** The type of each element. */
+  pub fn element(&self) -> &DataTypeInfo { &self.element }
+  /** The number of elements. */
+  pub fn num_elements(&self) -> usize { self.num_elements }
+  /** The distance between consecutive elements. */
+  pub fn stride(&self) -> usize { self.element.array_stride() }
+  pub fn size(&self) -> usize { self.stride() * self.num_elements }
+  pub fn align(&self) -> usize { self.element.align() }
+  pub fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn wgsl_name(&self) -> &SharedName { &self.wgsl_name }
+}
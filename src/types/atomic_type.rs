@@ -0,0 +1,115 @@
+use super::{
+  DataType,
+  PrimType,
+  PrimTypeInfo,
+  ScalarType,
+  ScalarTypeInfo,
+  DataTypeSpecialize,
+  DataTypeSpecializeRef,
+  DataTypeSpecializeMut,
+};
+
+/**
+ * Every data type except `Atomic<S>`.  Only these can be loaded from
+ * buffers, written as literals or held by records and arrays, since
+ * WGSL only reaches atomics through the atomic operations:
+ *
+ * ```compile_fail
+ * # use pewter::{Pewter, PewterError, Atomic, lit};
+ * # fn declare(pewter: &Pewter) -> Result<(), PewterError> {
+ * pewter.declare(|decl| {
+ *   decl.shader_file("counts", |decl_sh| {
+ *     let counts = decl_sh.use_buf::<Atomic<u32>>("counts") ?;
+ *     decl_sh.entry_function_1d("counts_entry", |decl_sf| {
+ *       let count = counts.load(decl_sf.get_arg());
+ *       decl_sf.let_("count", count);
+ *       Ok(())
+ *     })
+ *   }) ?;
+ *   Ok(())
+ * })
+ * # }
+ * ```
+ *
+ * ```compile_fail
+ * # use pewter::{Atomic, lit};
+ * let three = lit(Atomic(3_u32));
+ * ```
+ */
+pub trait NonAtomic: DataType {}
+
+/** The scalars which can be held by an atomic, `u32` and `i32`. */
+pub trait AtomicScalar: ScalarType + Eq {
+  const ATOMIC_INFO: AtomicTypeInfo;
+}
+
+impl AtomicScalar for u32 {
+  const ATOMIC_INFO: AtomicTypeInfo = AtomicTypeInfo::U32;
+}
+impl AtomicScalar for i32 {
+  const ATOMIC_INFO: AtomicTypeInfo = AtomicTypeInfo::I32;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AtomicTypeInfo {
+  U32, I32,
+}
+impl AtomicTypeInfo {
+  pub const fn into_prim(self) -> PrimTypeInfo {
+    PrimTypeInfo::Atomic(self)
+  }
+  pub const fn size(&self) -> usize { 4 }
+  pub const fn align(&self) -> usize { 4 }
+  pub const fn name(&self) -> &'static str {
+    match *self {
+      AtomicTypeInfo::U32 => "atomic<u32>",
+      AtomicTypeInfo::I32 => "atomic<i32>",
+    }
+  }
+  /** The scalar held by the atomic. */
+  pub const fn scalar(&self) -> ScalarTypeInfo {
+    match *self {
+      AtomicTypeInfo::U32 => ScalarTypeInfo::U32,
+      AtomicTypeInfo::I32 => ScalarTypeInfo::I32,
+    }
+  }
+}
+
+/**
+ * An atomic `u32` or `i32`, WGSL's `atomic<T>`.  Atomics can only be
+ * held by read-write storage buffers, and are only accessed by the
+ * atomic operations of shader function sessions.  The host reads
+ * and writes them as plain scalars.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Atomic<S: AtomicScalar>(pub S);
+
+impl<S: AtomicScalar> Atomic<S> {
+  pub fn new(value: S) -> Self { Atomic(value) }
+  pub fn into_inner(self) -> S { self.0 }
+}
+impl<S: AtomicScalar> From<S> for Atomic<S> {
+  fn from(value: S) -> Self { Atomic(value) }
+}
+
+impl<S: AtomicScalar> DataType for Atomic<S> {
+  fn specialize<Spez: DataTypeSpecialize>(spez: Spez) -> Spez::Result {
+    spez.prim::<Atomic<S>>()
+  }
+  fn specialize_ref<Spez: DataTypeSpecializeRef>(&self, spez: Spez) {
+    spez.prim::<Atomic<S>>(self);
+  }
+  fn specialize_mut<Spez: DataTypeSpecializeMut>(&mut self, spez: Spez) {
+    spez.prim::<Atomic<S>>(self);
+  }
+}
+impl<S: AtomicScalar> PrimType for Atomic<S> {
+  const PRIM_INFO: PrimTypeInfo = S::ATOMIC_INFO.into_prim();
+  fn write_bytes(&self, bytes_out: &mut [u8]) {
+    self.0.write_bytes(bytes_out);
+  }
+  fn read_bytes(&mut self, bytes_in: &[u8]) {
+    self.0.read_bytes(bytes_in);
+  }
+}
+
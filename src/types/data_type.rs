@@ -5,6 +5,9 @@ use super::{
   PrimType,
   RecordType,
   RecordTypeInfo,
+  ArrayType,
+  ArrayTypeInfo,
+  SharedName,
   record_info_for_type,
 };
 
@@ -25,14 +28,17 @@ pub trait DataTypeSpecialize {
   type Result;
   fn record<RT: RecordType>(self) -> Self::Result;
   fn prim<PT: PrimType>(self) -> Self::Result;
+  fn array<AT: ArrayType>(self) -> Self::Result;
 }
 pub trait DataTypeSpecializeRef {
   fn record<RT: RecordType>(self, rec: &RT);
   fn prim<PT: PrimType>(self, prim: &PT);
+  fn array<AT: ArrayType>(self, array: &AT);
 }
 pub trait DataTypeSpecializeMut {
   fn record<RT: RecordType>(self, rec: &mut RT);
   fn prim<PT: PrimType>(self, prim: &mut PT);
+  fn array<AT: ArrayType>(self, array: &mut AT);
 }
 
 
@@ -53,6 +59,14 @@ impl<'a> DataTypeSpecializeRef for WriteSpecializer<'a> {
   fn record<RT: RecordType>(self, rec: &RT) {
     record_info_for_type::<RT>().write_value(rec, self.bytes_out);
   }
+  fn array<AT: ArrayType>(self, array: &AT) {
+    let elem_type = data_type_info_for::<AT::Element>();
+    let (size, stride) = (elem_type.size(), elem_type.array_stride());
+    for (i, elem) in array.elements().iter().enumerate() {
+      let start = i * stride;
+      data_type_write(elem, &mut self.bytes_out[start .. start + size]);
+    }
+  }
 }
 
 /**
@@ -72,6 +86,14 @@ impl<'a> DataTypeSpecializeMut for ReadSpecializer<'a> {
   fn record<RT: RecordType>(self, rec: &mut RT) {
     record_info_for_type::<RT>().read_value(rec, self.bytes);
   }
+  fn array<AT: ArrayType>(self, array: &mut AT) {
+    let elem_type = data_type_info_for::<AT::Element>();
+    let (size, stride) = (elem_type.size(), elem_type.array_stride());
+    for (i, elem) in array.elements_mut().iter_mut().enumerate() {
+      let start = i * stride;
+      data_type_read(elem, &self.bytes[start .. start + size]);
+    }
+  }
 }
 
 /**
@@ -81,26 +103,89 @@ impl<'a> DataTypeSpecializeMut for ReadSpecializer<'a> {
 pub enum DataTypeInfo {
   Prim(PrimTypeInfo),
   Record(RecordTypeInfo),
+  Array(ArrayTypeInfo),
 }
 impl DataTypeInfo {
   pub fn size(&self) -> usize {
-    match self {
-      &DataTypeInfo::Prim(ref prim) => prim.size(),
-      &DataTypeInfo::Record(ref record) => record.size(),
+    match *self {
+      DataTypeInfo::Prim(ref prim) => prim.size(),
+      DataTypeInfo::Record(ref record) => record.size(),
+      DataTypeInfo::Array(ref array) => array.size(),
     }
   }
   pub fn align(&self) -> usize {
-    match self {
-      &DataTypeInfo::Prim(ref prim) => prim.align(),
-      &DataTypeInfo::Record(ref record) => record.align(),
+    match *self {
+      DataTypeInfo::Prim(ref prim) => prim.align(),
+      DataTypeInfo::Record(ref record) => record.align(),
+      DataTypeInfo::Array(ref array) => array.align(),
     }
   }
   pub fn name(&self) -> &str {
-    match self {
-      &DataTypeInfo::Prim(info) => info.name(),
-      &DataTypeInfo::Record(ref record_type) => record_type.name(),
+    match *self {
+      DataTypeInfo::Prim(info) => info.name(),
+      DataTypeInfo::Record(ref record_type) => record_type.name(),
+      DataTypeInfo::Array(ref array) => array.name(),
+    }
+  }
+  /**
+   * Whether values can be shared with the host through buffers.
+   * Records are host-shareable when all of their fields are, and
+   * arrays when their elements are.
+   */
+  pub fn is_host_shareable(&self) -> bool {
+    match *self {
+      DataTypeInfo::Prim(ref prim) => prim.is_host_shareable(),
+      DataTypeInfo::Record(ref record) => record.record_info().fields()
+        .iter()
+        .all(|f| f.data_type().is_host_shareable()),
+      DataTypeInfo::Array(ref array) => array.element().is_host_shareable(),
+    }
+  }
+  /**
+   * Whether values hold atomics, which are only allowed in
+   * read-write storage buffers.
+   */
+  pub(crate) fn has_atomics(&self) -> bool {
+    match *self {
+      DataTypeInfo::Prim(prim) => matches!(prim, PrimTypeInfo::Atomic(_)),
+      DataTypeInfo::Record(ref record) => record.record_info().fields()
+        .iter()
+        .any(|f| f.data_type().has_atomics()),
+      DataTypeInfo::Array(ref array) => array.element().has_atomics(),
+    }
+  }
+  /** The name of the type in generated WGSL. */
+  pub(crate) fn wgsl_name(&self) -> &str {
+    match *self {
+      DataTypeInfo::Prim(info) => info.name(),
+      DataTypeInfo::Record(ref record_type) => record_type.wgsl_name(),
+      DataTypeInfo::Array(ref array) => array.wgsl_name(),
     }
   }
+  /**
+   * The name of a record field which keeps this type from being
+   * held by a uniform buffer, if any.  Arrays in uniform buffers
+   * need a stride which is a multiple of 16, and an array breaking
+   * that is named itself.
+   */
+  pub(crate) fn uniform_layout_violation(&self) -> Option<SharedName> {
+    match *self {
+      DataTypeInfo::Prim(_) => None,
+      DataTypeInfo::Record(ref record) =>
+        record.record_info().uniform_layout_violation(),
+      DataTypeInfo::Array(ref array) => {
+        if array.stride() % 16 != 0 {
+          return Some(array.name().clone());
+        }
+        array.element().uniform_layout_violation()
+      },
+    }
+  }
+  /** The distance between consecutive elements of an array. */
+  pub fn array_stride(&self) -> usize {
+    let align = self.align();
+    (self.size() + (align - 1)) & !(align - 1)
+  }
 }
 
 pub(crate) fn data_type_info_for<DT: DataType>() -> DataTypeInfo {
@@ -115,4 +200,7 @@ impl DataTypeSpecialize for InfoSpecializer {
   fn record<RT: RecordType>(self) -> DataTypeInfo {
     record_info_for_type::<RT>().into_untyped().into_data_type()
   }
+  fn array<AT: ArrayType>(self) -> DataTypeInfo {
+    ArrayTypeInfo::for_type::<AT>().into_data_type()
+  }
 }
\ No newline at end of file
@@ -0,0 +1,148 @@
+
+use super::{
+  DataType,
+  PrimType,
+  PrimTypeInfo,
+  VectorType,
+  VectorTypeInfo,
+  DataTypeSpecialize,
+  DataTypeSpecializeRef,
+  DataTypeSpecializeMut,
+  NonAtomic,
+};
+
+/**
+ * Matrices of `f32`, held as an array of columns: `[[f32; R]; C]`
+ * is WGSL's `matCxR<f32>`, with `C` columns of `R` rows.
+ */
+pub trait MatrixType: PrimType {
+  const NAME: &'static str;
+  const MATRIX_INFO: MatrixTypeInfo;
+  const COLUMNS: usize;
+  const ROWS: usize;
+  type Column: VectorType<Field = f32>;
+  type Transposed: MatrixType;
+}
+
+/** `MatCxR` is the matrix of `C` columns and `R` rows. */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MatrixTypeInfo {
+  Mat2x2, Mat2x3, Mat2x4,
+  Mat3x2, Mat3x3, Mat3x4,
+  Mat4x2, Mat4x3, Mat4x4,
+}
+impl MatrixTypeInfo {
+  pub const fn into_prim(self) -> PrimTypeInfo {
+    PrimTypeInfo::Matrix(self)
+  }
+  /**
+   * Matrices are laid out as an array of their columns, so each
+   * column of three rows is padded like a `vec3`.
+   */
+  pub const fn size(&self) -> usize {
+    self.columns() * self.column_stride()
+  }
+  pub const fn align(&self) -> usize {
+    self.column().align()
+  }
+  /** The distance between the starts of consecutive columns. */
+  pub const fn column_stride(&self) -> usize {
+    self.column().align()
+  }
+  pub const fn name(&self) -> &'static str {
+    match *self {
+      MatrixTypeInfo::Mat2x2 => "mat2x2<f32>",
+      MatrixTypeInfo::Mat2x3 => "mat2x3<f32>",
+      MatrixTypeInfo::Mat2x4 => "mat2x4<f32>",
+
+      MatrixTypeInfo::Mat3x2 => "mat3x2<f32>",
+      MatrixTypeInfo::Mat3x3 => "mat3x3<f32>",
+      MatrixTypeInfo::Mat3x4 => "mat3x4<f32>",
+
+      MatrixTypeInfo::Mat4x2 => "mat4x2<f32>",
+      MatrixTypeInfo::Mat4x3 => "mat4x3<f32>",
+      MatrixTypeInfo::Mat4x4 => "mat4x4<f32>",
+    }
+  }
+  /** The number of columns. */
+  pub const fn columns(&self) -> usize {
+    match *self {
+      MatrixTypeInfo::Mat2x2 |
+      MatrixTypeInfo::Mat2x3 |
+      MatrixTypeInfo::Mat2x4 => 2,
+
+      MatrixTypeInfo::Mat3x2 |
+      MatrixTypeInfo::Mat3x3 |
+      MatrixTypeInfo::Mat3x4 => 3,
+
+      MatrixTypeInfo::Mat4x2 |
+      MatrixTypeInfo::Mat4x3 |
+      MatrixTypeInfo::Mat4x4 => 4,
+    }
+  }
+  /** The type of each column, a vector with one component per row. */
+  pub const fn column(&self) -> VectorTypeInfo {
+    match *self {
+      MatrixTypeInfo::Mat2x2 |
+      MatrixTypeInfo::Mat3x2 |
+      MatrixTypeInfo::Mat4x2 => VectorTypeInfo::F32x2,
+
+      MatrixTypeInfo::Mat2x3 |
+      MatrixTypeInfo::Mat3x3 |
+      MatrixTypeInfo::Mat4x3 => VectorTypeInfo::F32x3,
+
+      MatrixTypeInfo::Mat2x4 |
+      MatrixTypeInfo::Mat3x4 |
+      MatrixTypeInfo::Mat4x4 => VectorTypeInfo::F32x4,
+    }
+  }
+}
+
+macro_rules! impl_matrix {
+  ($cols:literal, $rows:literal, $info:ident, $name:literal) => {
+    impl DataType for [[f32; $rows]; $cols] {
+      fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+        spez.prim::<[[f32; $rows]; $cols]>()
+      }
+      fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+        spez.prim::<[[f32; $rows]; $cols]>(self);
+      }
+      fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+        spez.prim::<[[f32; $rows]; $cols]>(self);
+      }
+    }
+    impl NonAtomic for [[f32; $rows]; $cols] {}
+    impl PrimType for [[f32; $rows]; $cols] {
+      const PRIM_INFO: PrimTypeInfo = Self::MATRIX_INFO.into_prim();
+      fn write_bytes(&self, bytes_out: &mut [u8]) {
+        let stride = Self::MATRIX_INFO.column_stride();
+        for (i, column) in self.iter().enumerate() {
+          column.write_bytes(&mut bytes_out[i * stride ..]);
+        }
+      }
+      fn read_bytes(&mut self, bytes_in: &[u8]) {
+        let stride = Self::MATRIX_INFO.column_stride();
+        for (i, column) in self.iter_mut().enumerate() {
+          column.read_bytes(&bytes_in[i * stride ..]);
+        }
+      }
+    }
+    impl MatrixType for [[f32; $rows]; $cols] {
+      const NAME: &'static str = $name;
+      const MATRIX_INFO: MatrixTypeInfo = MatrixTypeInfo::$info;
+      const COLUMNS: usize = $cols;
+      const ROWS: usize = $rows;
+      type Column = [f32; $rows];
+      type Transposed = [[f32; $cols]; $rows];
+    }
+  };
+}
+impl_matrix!(2, 2, Mat2x2, "mat2x2<f32>");
+impl_matrix!(2, 3, Mat2x3, "mat2x3<f32>");
+impl_matrix!(2, 4, Mat2x4, "mat2x4<f32>");
+impl_matrix!(3, 2, Mat3x2, "mat3x2<f32>");
+impl_matrix!(3, 3, Mat3x3, "mat3x3<f32>");
+impl_matrix!(3, 4, Mat3x4, "mat3x4<f32>");
+impl_matrix!(4, 2, Mat4x2, "mat4x2<f32>");
+impl_matrix!(4, 3, Mat4x3, "mat4x3<f32>");
+impl_matrix!(4, 4, Mat4x4, "mat4x4<f32>");
@@ -3,8 +3,11 @@ mod shared_name;
 mod prim_type;
 mod scalar_type;
 mod vector_type;
+mod matrix_type;
 mod record_type;
 mod record_info;
+mod array_type;
+mod atomic_type;
 
 mod data_type;
 mod data_type_tuple;
@@ -14,11 +17,6 @@ pub(crate) use self::{
     intern_name,
   },
   data_type::{
-    DataType,
-    DataTypeInfo,
-    DataTypeSpecialize,
-    DataTypeSpecializeMut,
-    DataTypeSpecializeRef,
     data_type_info_for,
     data_type_read,
     data_type_write,
@@ -35,10 +33,22 @@ pub use self::{
   shared_name::{
     SharedName,
   },
+  data_type::{
+    DataType,
+    DataTypeInfo,
+    DataTypeSpecialize,
+    DataTypeSpecializeMut,
+    DataTypeSpecializeRef,
+  },
   record_type::{
     RecordType,
     RecordTypeFieldSpecifier,
   },
+  array_type::{
+    ArrayType,
+    ArrayTypeInfo,
+    FixedArray,
+  },
   record_info::{
     RecordInfo,
     TypedRecordInfo,
@@ -53,6 +63,17 @@ pub use self::{
   vector_type::{
     VectorType,
     VectorTypeInfo,
+    VectorOf,
+  },
+  matrix_type::{
+    MatrixType,
+    MatrixTypeInfo,
+  },
+  atomic_type::{
+    Atomic,
+    AtomicScalar,
+    AtomicTypeInfo,
+    NonAtomic,
   },
   prim_type::{
     PrimType,
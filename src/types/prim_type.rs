@@ -1,7 +1,7 @@
 use super::{
   DataType,
   ScalarTypeInfo,
-  VectorTypeInfo, DataTypeInfo
+  VectorTypeInfo, MatrixTypeInfo, AtomicTypeInfo, DataTypeInfo
 };
 
 /**
@@ -17,6 +17,9 @@ pub trait PrimType: DataType {
 pub enum PrimTypeInfo {
   Scalar(ScalarTypeInfo),
   Vector(VectorTypeInfo),
+  Matrix(MatrixTypeInfo),
+  // Only usable in read-write storage buffers.
+  Atomic(AtomicTypeInfo),
 }
 impl PrimTypeInfo {
   pub(crate) fn into_data_type(self) -> DataTypeInfo {
@@ -26,20 +29,36 @@ impl PrimTypeInfo {
     match self {
       PrimTypeInfo::Scalar(info) => info.name(),
       PrimTypeInfo::Vector(info) => info.name(),
+      PrimTypeInfo::Matrix(info) => info.name(),
+      PrimTypeInfo::Atomic(info) => info.name(),
     }
   }
 }
 impl PrimTypeInfo {
   pub const fn size(&self) -> usize {
-    match self {
-      &PrimTypeInfo::Scalar(ref scalar_info) => scalar_info.size(),
-      &PrimTypeInfo::Vector(ref vector_info) => vector_info.size(),
+    match *self {
+      PrimTypeInfo::Scalar(ref scalar_info) => scalar_info.size(),
+      PrimTypeInfo::Vector(ref vector_info) => vector_info.size(),
+      PrimTypeInfo::Matrix(ref matrix_info) => matrix_info.size(),
+      PrimTypeInfo::Atomic(ref atomic_info) => atomic_info.size(),
     }
   }
   pub const fn align(&self) -> usize {
-    match self {
-      &PrimTypeInfo::Scalar(ref scalar_info) => scalar_info.align(),
-      &PrimTypeInfo::Vector(ref vector_info) => vector_info.align(),
+    match *self {
+      PrimTypeInfo::Scalar(ref scalar_info) => scalar_info.align(),
+      PrimTypeInfo::Vector(ref vector_info) => vector_info.align(),
+      PrimTypeInfo::Matrix(ref matrix_info) => matrix_info.align(),
+      PrimTypeInfo::Atomic(ref atomic_info) => atomic_info.align(),
+    }
+  }
+  pub const fn is_host_shareable(&self) -> bool {
+    match *self {
+      PrimTypeInfo::Scalar(ref scalar_info) =>
+        scalar_info.is_host_shareable(),
+      PrimTypeInfo::Vector(ref vector_info) =>
+        vector_info.is_host_shareable(),
+      PrimTypeInfo::Matrix(_) |
+      PrimTypeInfo::Atomic(_) => true,
     }
   }
 }
\ No newline at end of file
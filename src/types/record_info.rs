@@ -2,7 +2,7 @@
 use std::{
   fmt, mem,
   any::TypeId,
-  collections::HashMap,
+  collections::{HashMap, HashSet},
   marker::PhantomData,
   sync::{Arc, Mutex},
 };
@@ -14,6 +14,9 @@ use super::{
   RecordTypeFieldSpecifier,
   DataType,
   RecordTypeInfo,
+  ArrayType,
+  ArrayTypeInfo,
+  NonAtomic,
   intern_name,
   data_type_write,
   data_type_read,
@@ -25,6 +28,7 @@ use super::{
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct RecordInfo {
   name: SharedName,
+  wgsl_name: SharedName,
   fields: Vec<RecordFieldInfo>,
   size: usize,
   align: usize,
@@ -33,6 +37,45 @@ impl RecordInfo {
   pub(crate) fn size(&self) -> usize { self.size }
   pub(crate) fn align(&self) -> usize { self.align }
   pub(crate) fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn wgsl_name(&self) -> &SharedName { &self.wgsl_name }
+  pub(crate) fn fields(&self) -> &[RecordFieldInfo] { &self.fields }
+
+  /**
+   * Find a field breaking the layout rules of the uniform address
+   * space, which are stricter than those of storage buffers: a
+   * nested record or array must start at a multiple of 16 bytes,
+   * and the field after it must start at least its size rounded up
+   * to 16 bytes later.  The stride of an array must also be a
+   * multiple of 16 bytes.
+   */
+  pub(crate) fn uniform_layout_violation(&self) -> Option<SharedName> {
+    for (i, field) in self.fields.iter().enumerate() {
+      let size = match field.data_type {
+        DataTypeInfo::Record(ref record) => record.size(),
+        DataTypeInfo::Array(ref array) => {
+          if array.stride() % 16 != 0 {
+            return Some(field.name.clone());
+          }
+          array.size()
+        },
+        _ => continue,
+      };
+      if field.offset % 16 != 0 {
+        return Some(field.name.clone());
+      }
+      if let Some(next) = self.fields.get(i + 1) {
+        let min_offset = field.offset + ((size + 15) & !15);
+        if next.offset < min_offset {
+          return Some(next.name.clone());
+        }
+      }
+      let nested = field.data_type.uniform_layout_violation();
+      if nested.is_some() {
+        return nested;
+      }
+    }
+    None
+  }
 
   pub(crate) unsafe fn write_value<RT>(&self,
     rec: &RT,
@@ -67,6 +110,16 @@ lazy_static::lazy_static! {
     = Arc::new(Mutex::new(HashMap::new()));
 }
 
+/*
+ * Global set of the WGSL names given to record types, so that no
+ * two record types share a struct name.
+ */
+lazy_static::lazy_static! {
+  static ref RECORD_WGSL_NAMES
+    : Arc<Mutex<HashSet<String>>>
+    = Arc::new(Mutex::new(HashSet::new()));
+}
+
 /**
  * Get the shared record information for a `RecordType`.
  */
@@ -100,13 +153,15 @@ pub(crate) fn record_info_for_type<RT: RecordType>()
       locked.insert(type_id, record_info.clone());
       record_info
     };
-    return TypedRecordInfo { inner, _dummy: PhantomData };
+    TypedRecordInfo { inner, _dummy: PhantomData }
   }
 }
 
 fn build_record_info<RT: RecordType>() -> RecordInfo {
+  let type_name = std::any::type_name::<RT>();
   let mut builder = Builder {
-    name: intern_name(std::any::type_name::<RT>()),
+    name: intern_name(type_name),
+    wgsl_name: intern_name(&unique_wgsl_name(type_name)),
     fields: Vec::new(),
     cur_size: 0,
     max_align: 0,
@@ -115,15 +170,58 @@ fn build_record_info<RT: RecordType>() -> RecordInfo {
   builder.finish()
 }
 
+/**
+ * Make a WGSL identifier from a rust type name, by joining the
+ * runs of characters valid in an identifier with underscores.
+ * `crate::module::Point` becomes `crate_module_Point`.
+ */
+fn wgsl_identifier(type_name: &str) -> String {
+  type_name
+    .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+    .map(|part| part.trim_matches('_'))
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join("_")
+}
+
+/**
+ * Reserve a WGSL struct name for the rust type `type_name`.  The
+ * identifier of a type name can collide with another's, e.g.
+ * `a::b_c::D` and `a::b::c_D`, so a taken name gets the first
+ * free numeric suffix.
+ */
+fn unique_wgsl_name(type_name: &str) -> String {
+  let base = wgsl_identifier(type_name);
+  let mut locked = RECORD_WGSL_NAMES.lock()
+    .expect("Failed to lock RECORD_WGSL_NAMES");
+  let mut name = base.clone();
+  let mut suffix = 2;
+  while locked.contains(&name) {
+    name = format!("{}_{}", base, suffix);
+    suffix += 1;
+  }
+  locked.insert(name.clone());
+  name
+}
+
+/** Encodes a field of a record, given a reference to the record. */
+type FieldWriter = Box<dyn Send + Sync + Fn (&u8, &mut [u8])>;
+/** Decodes a field into a record, given a reference to the record. */
+type FieldReader = Box<dyn Send + Sync + Fn (&mut u8, &[u8])>;
+
 pub(crate) struct RecordFieldInfo {
   name: SharedName,
   data_type: DataTypeInfo,
   offset: usize,
 
-  writer: Box<dyn Send + Sync + Fn (&u8, &mut [u8])>,
-  reader: Box<dyn Send + Sync + Fn (&mut u8, &[u8])>,
+  writer: FieldWriter,
+  reader: FieldReader,
 }
 impl RecordFieldInfo {
+  pub(crate) fn name(&self) -> &SharedName { &self.name }
+  pub(crate) fn data_type(&self) -> &DataTypeInfo { &self.data_type }
+  pub(crate) fn offset(&self) -> usize { self.offset }
+
   fn end_offset(&self) -> usize {
     self.offset + self.data_type.size()
   }
@@ -159,7 +257,7 @@ impl SharedRecordInfo {
 }
 impl std::ops::Deref for SharedRecordInfo {
   type Target = RecordInfo;
-  fn deref(&self) -> &RecordInfo { &*self.0 }
+  fn deref(&self) -> &RecordInfo { &self.0 }
 }
 
 /**
@@ -187,6 +285,7 @@ impl<RT: RecordType> std::ops::Deref for TypedRecordInfo<RT> {
 /* Helper to build a `RecordInfo` from a `RecordType`. */
 struct Builder {
   name: SharedName,
+  wgsl_name: SharedName,
   fields: Vec<RecordFieldInfo>,
   cur_size: usize,
   max_align: usize,
@@ -195,8 +294,8 @@ impl Builder {
   fn add_field(&mut self,
     name: &str,
     data_type: DataTypeInfo,
-    writer: Box<dyn Send + Sync + Fn (&u8, &mut [u8])>,
-    reader: Box<dyn Send + Sync + Fn (&mut u8, &[u8])>,
+    writer: FieldWriter,
+    reader: FieldReader,
   ) {
     let name = intern_name(name);
     let align = data_type.align();
@@ -217,6 +316,7 @@ impl Builder {
     let max_align = self.max_align;
     RecordInfo {
       name: self.name,
+      wgsl_name: self.wgsl_name,
       fields: self.fields,
       size: (self.cur_size + (max_align - 1)) & !(max_align - 1),
       align: max_align,
@@ -225,7 +325,7 @@ impl Builder {
 }
 impl<RT: RecordType> RecordTypeFieldSpecifier<RT> for Builder {
   fn prim_field<FT, Get, Set>(&mut self, name: &str, get: Get, set: Set)
-    where FT: PrimType,
+    where FT: PrimType + NonAtomic,
           Get: 'static + Send + Sync + Fn (&RT) -> FT,
           Set: 'static + Send + Sync + Fn (&mut RT, FT)
   {
@@ -251,20 +351,33 @@ impl<RT: RecordType> RecordTypeFieldSpecifier<RT> for Builder {
       make_read_field(set),
     );
   }
+
+  fn array_field<FT, Get, Set>(&mut self, name: &str, get: Get, set: Set)
+    where FT: ArrayType + NonAtomic,
+          Get: 'static + Send + Sync + Fn (&RT) -> FT,
+          Set: 'static + Send + Sync + Fn (&mut RT, FT)
+  {
+    self.add_field(
+      name,
+      ArrayTypeInfo::for_type::<FT>().into_data_type(),
+      make_write_field(get),
+      make_read_field(set),
+    );
+  }
 }
 
-fn make_write_field<RT, FT, Get>(get: Get) -> Box<dyn Send + Sync + Fn (&u8, &mut [u8])>
+fn make_write_field<RT, FT, Get>(get: Get) -> FieldWriter
   where RT: RecordType,
         FT: DataType,
         Get: 'static + Send + Sync + Fn (&RT) -> FT,
 {
   Box::new(move |f: &u8, bytes_out: &mut [u8]| {
-    let p = unsafe { mem::transmute::<_, &RT>(f) };
+    let p = unsafe { mem::transmute::<&u8, &RT>(f) };
     let field_value = get(p);
     data_type_write(&field_value, bytes_out);
   })
 }
-fn make_read_field<RT, FT, Set>(set: Set) -> Box<dyn Send + Sync + Fn (&mut u8, &[u8])>
+fn make_read_field<RT, FT, Set>(set: Set) -> FieldReader
   where RT: RecordType,
         FT: DataType,
         Set: 'static + Send + Sync + Fn (&mut RT, FT),
@@ -272,7 +385,7 @@ fn make_read_field<RT, FT, Set>(set: Set) -> Box<dyn Send + Sync + Fn (&mut u8,
   Box::new(move |f: &mut u8, bytes: &[u8]| {
     let mut field_value: FT = Default::default();
     data_type_read(&mut field_value, bytes);
-    let p = unsafe { mem::transmute::<_, &mut RT>(f) };
+    let p = unsafe { mem::transmute::<&mut u8, &mut RT>(f) };
     set(p, field_value);
   })
 }
\ No newline at end of file
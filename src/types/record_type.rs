@@ -1,4 +1,6 @@
-use super::{DataType, PrimType, SharedRecordInfo, SharedName};
+use super::{
+  DataType, PrimType, ArrayType, NonAtomic, SharedRecordInfo, SharedName,
+};
 
 
 /**
@@ -9,9 +11,12 @@ pub trait RecordType: DataType {
   fn specify_fields<FS: RecordTypeFieldSpecifier<Self>>(fs: &mut FS);
 }
 
+/** Records can only hold non-atomic fields, see `prim_field`. */
+impl<RT: RecordType> NonAtomic for RT {}
+
 pub trait RecordTypeFieldSpecifier<RT: RecordType> {
   fn prim_field<FT, Get, Set>(&mut self, name: &str, get: Get, set: Set)
-    where FT: PrimType,
+    where FT: PrimType + NonAtomic,
           Get: 'static + Send + Sync + Fn (&RT) -> FT,
           Set: 'static + Send + Sync + Fn (&mut RT, FT);
 
@@ -19,6 +24,11 @@ pub trait RecordTypeFieldSpecifier<RT: RecordType> {
     where FT: RecordType,
           Get: 'static + Send + Sync + Fn (&RT) -> FT,
           Set: 'static + Send + Sync + Fn (&mut RT, FT);
+
+  fn array_field<FT, Get, Set>(&mut self, name: &str, get: Get, set: Set)
+    where FT: ArrayType + NonAtomic,
+          Get: 'static + Send + Sync + Fn (&RT) -> FT,
+          Set: 'static + Send + Sync + Fn (&mut RT, FT);
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -36,6 +46,12 @@ impl RecordTypeInfo {
     self.record_info.align()
   }
   pub fn name(&self) -> &SharedName {
-    &self.record_info.name()
+    self.record_info.name()
+  }
+  pub(crate) fn wgsl_name(&self) -> &SharedName {
+    self.record_info.wgsl_name()
+  }
+  pub(crate) fn record_info(&self) -> &SharedRecordInfo {
+    &self.record_info
   }
 }
\ No newline at end of file
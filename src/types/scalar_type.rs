@@ -3,6 +3,7 @@ use super::{
   DataType,
   PrimTypeInfo,
   DataTypeSpecialize, DataTypeSpecializeRef, DataTypeSpecializeMut,
+  NonAtomic,
 };
 
 pub trait ScalarType: PrimType {
@@ -13,6 +14,8 @@ pub trait ScalarType: PrimType {
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ScalarTypeInfo {
   U32, I32, F32,
+  // Only usable in shader code: bool is not host-shareable.
+  Bool,
 }
 impl ScalarTypeInfo {
   pub const fn into_prim(self) -> PrimTypeInfo {
@@ -25,8 +28,13 @@ impl ScalarTypeInfo {
       ScalarTypeInfo::F32 => "f32",
       ScalarTypeInfo::U32 => "u32",
       ScalarTypeInfo::I32 => "i32",
+      ScalarTypeInfo::Bool => "bool",
     }
   }
+  /** Whether values can be shared with the host through buffers. */
+  pub const fn is_host_shareable(&self) -> bool {
+    !matches!(*self, ScalarTypeInfo::Bool)
+  }
 }
 
 impl DataType for u32 {
@@ -52,7 +60,7 @@ impl PrimType for u32 {
   }
 }
 impl ScalarType for u32 {
-  const NAME: &'static str = &"u32";
+  const NAME: &'static str = "u32";
   const SCALAR_INFO: ScalarTypeInfo = ScalarTypeInfo::U32;
 }
 
@@ -79,7 +87,7 @@ impl PrimType for i32 {
   }
 }
 impl ScalarType for i32 {
-  const NAME: &'static str = &"i32";
+  const NAME: &'static str = "i32";
   const SCALAR_INFO: ScalarTypeInfo = ScalarTypeInfo::I32;
 }
 
@@ -106,6 +114,38 @@ impl PrimType for f32 {
   }
 }
 impl ScalarType for f32 {
-  const NAME: &'static str = &"f32";
+  const NAME: &'static str = "f32";
   const SCALAR_INFO: ScalarTypeInfo = ScalarTypeInfo::F32;
-}
\ No newline at end of file
+}
+impl DataType for bool {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.prim::<bool>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.prim::<bool>(self);
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.prim::<bool>(self);
+  }
+}
+impl PrimType for bool {
+  const PRIM_INFO: PrimTypeInfo = Self::SCALAR_INFO.into_prim();
+  // Bools never reach buffers, but are encoded like a u32 of 0 or 1.
+  fn write_bytes(&self, bytes_out: &mut [u8]) {
+    bytes_out.copy_from_slice(&(*self as u32).to_le_bytes());
+  }
+  fn read_bytes(&mut self, bytes_in: &[u8]) {
+    let mut fixed: [u8; 4] = Default::default();
+    fixed.copy_from_slice(bytes_in);
+    *self = u32::from_le_bytes(fixed) != 0;
+  }
+}
+impl ScalarType for bool {
+  const NAME: &'static str = "bool";
+  const SCALAR_INFO: ScalarTypeInfo = ScalarTypeInfo::Bool;
+}
+
+impl NonAtomic for u32 {}
+impl NonAtomic for i32 {}
+impl NonAtomic for f32 {}
+impl NonAtomic for bool {}
@@ -4,9 +4,11 @@ use super::{
   PrimType,
   PrimTypeInfo,
   ScalarType,
+  ScalarTypeInfo,
   DataTypeSpecialize,
   DataTypeSpecializeRef,
   DataTypeSpecializeMut,
+  NonAtomic,
 };
 
 pub trait VectorType: PrimType {
@@ -16,11 +18,21 @@ pub trait VectorType: PrimType {
   type Field: ScalarType;
 }
 
+/**
+ * Scalar types with a vector of `N` components, `Vector`.  This
+ * maps a component type and count back to the vector type.
+ */
+pub trait VectorOf<const N: usize>: ScalarType {
+  type Vector: VectorType<Field = Self>;
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum VectorTypeInfo {
   U32x2, U32x3, U32x4,
   I32x2, I32x3, I32x4,
   F32x2, F32x3, F32x4,
+  // Only usable in shader code: bool is not host-shareable.
+  Boolx2, Boolx3, Boolx4,
 }
 impl VectorTypeInfo {
   pub const fn into_prim(self) -> PrimTypeInfo {
@@ -39,6 +51,10 @@ impl VectorTypeInfo {
       VectorTypeInfo::F32x2 => 8,
       VectorTypeInfo::F32x3 => 12,
       VectorTypeInfo::F32x4 => 16,
+
+      VectorTypeInfo::Boolx2 => 8,
+      VectorTypeInfo::Boolx3 => 12,
+      VectorTypeInfo::Boolx4 => 16,
     }
   }
   pub const fn align(&self) -> usize {
@@ -54,6 +70,10 @@ impl VectorTypeInfo {
       VectorTypeInfo::F32x2 => 8,
       VectorTypeInfo::F32x3 => 16,
       VectorTypeInfo::F32x4 => 16,
+
+      VectorTypeInfo::Boolx2 => 8,
+      VectorTypeInfo::Boolx3 => 16,
+      VectorTypeInfo::Boolx4 => 16,
     }
   }
   pub const fn name(&self) -> &'static str {
@@ -69,8 +89,56 @@ impl VectorTypeInfo {
       VectorTypeInfo::F32x2 => "vec2<f32>",
       VectorTypeInfo::F32x3 => "vec3<f32>",
       VectorTypeInfo::F32x4 => "vec4<f32>",
+
+      VectorTypeInfo::Boolx2 => "vec2<bool>",
+      VectorTypeInfo::Boolx3 => "vec3<bool>",
+      VectorTypeInfo::Boolx4 => "vec4<bool>",
+    }
+  }
+  /** The type of each component. */
+  pub const fn component(&self) -> ScalarTypeInfo {
+    match *self {
+      VectorTypeInfo::U32x2 |
+      VectorTypeInfo::U32x3 |
+      VectorTypeInfo::U32x4 => ScalarTypeInfo::U32,
+
+      VectorTypeInfo::I32x2 |
+      VectorTypeInfo::I32x3 |
+      VectorTypeInfo::I32x4 => ScalarTypeInfo::I32,
+
+      VectorTypeInfo::F32x2 |
+      VectorTypeInfo::F32x3 |
+      VectorTypeInfo::F32x4 => ScalarTypeInfo::F32,
+
+      VectorTypeInfo::Boolx2 |
+      VectorTypeInfo::Boolx3 |
+      VectorTypeInfo::Boolx4 => ScalarTypeInfo::Bool,
     }
   }
+  /** The number of components. */
+  pub const fn num_components(&self) -> usize {
+    match *self {
+      VectorTypeInfo::U32x2 |
+      VectorTypeInfo::I32x2 |
+      VectorTypeInfo::F32x2 |
+      VectorTypeInfo::Boolx2 => 2,
+
+      VectorTypeInfo::U32x3 |
+      VectorTypeInfo::I32x3 |
+      VectorTypeInfo::F32x3 |
+      VectorTypeInfo::Boolx3 => 3,
+
+      VectorTypeInfo::U32x4 |
+      VectorTypeInfo::I32x4 |
+      VectorTypeInfo::F32x4 |
+      VectorTypeInfo::Boolx4 => 4,
+    }
+  }
+  /** Whether values can be shared with the host through buffers. */
+  pub const fn is_host_shareable(&self) -> bool {
+    !matches!(*self,
+      VectorTypeInfo::Boolx2 | VectorTypeInfo::Boolx3 | VectorTypeInfo::Boolx4)
+  }
 }
 
 // U32
@@ -407,4 +475,139 @@ impl VectorType for [f32;4] {
   const VECTOR_INFO: VectorTypeInfo = VectorTypeInfo::F32x4;
   const SIZE: usize = 4;
   type Field = f32;
-}
\ No newline at end of file
+}
+// Bool
+impl DataType for [bool;2] {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.prim::<[bool;2]>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.prim::<[bool;2]>(self);
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.prim::<[bool;2]>(self);
+  }
+}
+impl PrimType for [bool;2] {
+  const PRIM_INFO: PrimTypeInfo = Self::VECTOR_INFO.into_prim();
+  fn write_bytes(&self, bytes_out: &mut [u8]) {
+    bytes_out[0..4].copy_from_slice(&(self[0] as u32).to_le_bytes());
+    bytes_out[4..8].copy_from_slice(&(self[1] as u32).to_le_bytes());
+  }
+  fn read_bytes(&mut self, bytes_in: &[u8]) {
+    let mut fixed: [[u8; 4]; 2] = Default::default();
+    fixed[0].copy_from_slice(&bytes_in[0..4]);
+    fixed[1].copy_from_slice(&bytes_in[4..8]);
+    *self = [
+      u32::from_le_bytes(fixed[0]) != 0,
+      u32::from_le_bytes(fixed[1]) != 0,
+    ];
+  }
+}
+impl VectorType for [bool;2] {
+  const NAME: &'static str = "vec2<bool>";
+  const VECTOR_INFO: VectorTypeInfo = VectorTypeInfo::Boolx2;
+  const SIZE: usize = 2;
+  type Field = bool;
+}
+
+impl DataType for [bool;3] {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.prim::<[bool;3]>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.prim::<[bool;3]>(self);
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.prim::<[bool;3]>(self);
+  }
+}
+impl PrimType for [bool;3] {
+  const PRIM_INFO: PrimTypeInfo = Self::VECTOR_INFO.into_prim();
+  fn write_bytes(&self, bytes_out: &mut [u8]) {
+    bytes_out[0..4].copy_from_slice(&(self[0] as u32).to_le_bytes());
+    bytes_out[4..8].copy_from_slice(&(self[1] as u32).to_le_bytes());
+    bytes_out[8..12].copy_from_slice(&(self[2] as u32).to_le_bytes());
+  }
+  fn read_bytes(&mut self, bytes_in: &[u8]) {
+    let mut fixed: [[u8; 4]; 3] = Default::default();
+    fixed[0].copy_from_slice(&bytes_in[0..4]);
+    fixed[1].copy_from_slice(&bytes_in[4..8]);
+    fixed[2].copy_from_slice(&bytes_in[8..12]);
+    *self = [
+      u32::from_le_bytes(fixed[0]) != 0,
+      u32::from_le_bytes(fixed[1]) != 0,
+      u32::from_le_bytes(fixed[2]) != 0,
+    ];
+  }
+}
+impl VectorType for [bool;3] {
+  const NAME: &'static str = "vec3<bool>";
+  const VECTOR_INFO: VectorTypeInfo = VectorTypeInfo::Boolx3;
+  const SIZE: usize = 3;
+  type Field = bool;
+}
+
+impl DataType for [bool;4] {
+  fn specialize<S: DataTypeSpecialize>(spez: S) -> S::Result {
+    spez.prim::<[bool;4]>()
+  }
+  fn specialize_ref<S: DataTypeSpecializeRef>(&self, spez: S) {
+    spez.prim::<[bool;4]>(self);
+  }
+  fn specialize_mut<S: DataTypeSpecializeMut>(&mut self, spez: S) {
+    spez.prim::<[bool;4]>(self);
+  }
+}
+impl PrimType for [bool;4] {
+  const PRIM_INFO: PrimTypeInfo = Self::VECTOR_INFO.into_prim();
+  fn write_bytes(&self, bytes_out: &mut [u8]) {
+    bytes_out[0..4].copy_from_slice(&(self[0] as u32).to_le_bytes());
+    bytes_out[4..8].copy_from_slice(&(self[1] as u32).to_le_bytes());
+    bytes_out[8..12].copy_from_slice(&(self[2] as u32).to_le_bytes());
+    bytes_out[12..16].copy_from_slice(&(self[3] as u32).to_le_bytes());
+  }
+  fn read_bytes(&mut self, bytes_in: &[u8]) {
+    let mut fixed: [[u8; 4]; 4] = Default::default();
+    fixed[0].copy_from_slice(&bytes_in[0..4]);
+    fixed[1].copy_from_slice(&bytes_in[4..8]);
+    fixed[2].copy_from_slice(&bytes_in[8..12]);
+    fixed[3].copy_from_slice(&bytes_in[12..16]);
+    *self = [
+      u32::from_le_bytes(fixed[0]) != 0,
+      u32::from_le_bytes(fixed[1]) != 0,
+      u32::from_le_bytes(fixed[2]) != 0,
+      u32::from_le_bytes(fixed[3]) != 0,
+    ];
+  }
+}
+impl VectorType for [bool;4] {
+  const NAME: &'static str = "vec4<bool>";
+  const VECTOR_INFO: VectorTypeInfo = VectorTypeInfo::Boolx4;
+  const SIZE: usize = 4;
+  type Field = bool;
+}
+
+macro_rules! impl_vector_of {
+  ($($scalar:ty),*) => {
+    $(
+      impl VectorOf<2> for $scalar { type Vector = [$scalar; 2]; }
+      impl VectorOf<3> for $scalar { type Vector = [$scalar; 3]; }
+      impl VectorOf<4> for $scalar { type Vector = [$scalar; 4]; }
+    )*
+  };
+}
+impl_vector_of!(u32, i32, f32, bool);
+
+impl NonAtomic for [u32;2] {}
+impl NonAtomic for [u32;3] {}
+impl NonAtomic for [u32;4] {}
+impl NonAtomic for [i32;2] {}
+impl NonAtomic for [i32;3] {}
+impl NonAtomic for [i32;4] {}
+impl NonAtomic for [f32;2] {}
+impl NonAtomic for [f32;3] {}
+impl NonAtomic for [f32;4] {}
+impl NonAtomic for [bool;2] {}
+impl NonAtomic for [bool;3] {}
+impl NonAtomic for [bool;4] {}